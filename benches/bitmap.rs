@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ntimetable::project::Project;
 use roaring::RoaringBitmap;
 
 fn bench_hashset(c: &mut Criterion) {
@@ -39,13 +40,56 @@ fn bench_bitmap(c: &mut Criterion) {
     }
 
     c.bench_function("roaring_bitmap_union", |b| {
-        b.iter(|| black_box(&set1 & &set2))
+        b.iter(|| black_box(&set1 | &set2))
     });
 
     c.bench_function("roaring_bitmap_intersection", |b| {
-        b.iter(|| black_box(&set1 | &set2))
+        b.iter(|| black_box(&set1 & &set2))
+    });
+}
+
+/// People conflicts of every pair of events: attendee sets against the conflict matrix
+fn bench_conflicts(c: &mut Criterion) {
+    let project = Project::parse("./converted/comp01");
+    let events = &project.events;
+
+    let mut group = c.benchmark_group("people_conflicts");
+
+    group.bench_function("hashset_intersection", |b| {
+        b.iter(|| {
+            let mut conflicts = 0;
+            for e1 in events.iter_all() {
+                for e2 in events.iter_all() {
+                    let attendees = events.event_attendees(e1);
+                    if attendees
+                        .intersection(events.event_attendees(e2))
+                        .next()
+                        .is_some()
+                    {
+                        conflicts += 1;
+                    }
+                }
+            }
+            black_box(conflicts)
+        })
     });
+
+    group.bench_function("bitmatrix", |b| {
+        b.iter(|| {
+            let mut conflicts = 0;
+            for e1 in events.iter_all() {
+                for e2 in events.iter_all() {
+                    if events.have_people_conflict(e1, e2) {
+                        conflicts += 1;
+                    }
+                }
+            }
+            black_box(conflicts)
+        })
+    });
+
+    group.finish();
 }
 
-criterion_group!(bitmap, bench_hashset, bench_bitmap);
+criterion_group!(bitmap, bench_hashset, bench_bitmap, bench_conflicts);
 criterion_main!(bitmap);
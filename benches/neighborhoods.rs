@@ -1,6 +1,6 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use ntimetable::{
-    neighborhoods::{greedy_room, relocation, swap},
+    neighborhoods::{greedy_room, relocation, repair_rooms, swap},
     project::Project,
 };
 use std::sync::mpsc::channel;
@@ -20,14 +20,14 @@ fn bench_neighborhoods(c: &mut Criterion) {
     let project = Project::parse("./demo");
 
     let s = ntimetable::initial::find_initial_solution(&project, false).unwrap();
-    let mut s = ntimetable::optimize::Solution::new(s);
-    s.fill_counter(&project);
+    let s = ntimetable::optimize::Solution::from_timemap(&s, &project).unwrap();
 
     bench_function!(c, s, project, "nhd_relocation", relocation::neighborhoods);
     bench_function!(c, s, project, "nhd_swap_room_only", swap::room_only);
     bench_function!(c, s, project, "nhd_swap_time_only", swap::time_only);
     bench_function!(c, s, project, "nhd_swap_time_and_room", swap::time_and_room);
     bench_function!(c, s, project, "nhd_greedy_room", greedy_room::neighborhoods);
+    bench_function!(c, s, project, "nhd_repair_rooms", repair_rooms::neighborhoods);
 }
 
 criterion_group!(neighborhoods, bench_neighborhoods);
@@ -9,21 +9,20 @@ struct MOSA(Vec<f32>);
 
 impl CanDominate for MOSA {
     fn dominates(&self, other: &Self) -> bool {
-        for (i, v) in other.0.iter().enumerate() {
-            if *v > self.0[i] {
-                return false;
-            }
-        }
-        true
+        dominates(&self.0, &other.0)
     }
 
-    fn compare_first_element(&self, other: &Self) -> Ordering {
-        self.0[0].partial_cmp(&other.0[0]).unwrap()
+    fn compare_lexicographic(&self, other: &Self) -> Ordering {
+        compare_lexicographic(&self.0, &other.0)
     }
 
     fn sum(&self) -> f32 {
         self.0.iter().sum()
     }
+
+    fn objectives(&self) -> &[f32] {
+        &self.0
+    }
 }
 
 fn bench_mosa(c: &mut Criterion) {
@@ -53,6 +52,30 @@ fn bench_mosa(c: &mut Criterion) {
         })
     });
 
+    c.bench_function("sort_filter_mosa", |b| {
+        b.iter(|| {
+            black_box(sort_filter_mosa(data.clone(), 10.0));
+        })
+    });
+
+    c.bench_function("sort_filter", |b| {
+        b.iter(|| {
+            black_box(sort_filter(data.clone()));
+        })
+    });
+
+    c.bench_function("best_order_filter_mosa", |b| {
+        b.iter(|| {
+            black_box(best_order_filter_mosa(data.clone(), 10.0));
+        })
+    });
+
+    c.bench_function("best_order_filter", |b| {
+        b.iter(|| {
+            black_box(best_order_filter(data.clone()));
+        })
+    });
+
     c.bench_function("naive", |b| {
         b.iter(|| {
             black_box(naive(data.clone()));
@@ -60,5 +83,68 @@ fn bench_mosa(c: &mut Criterion) {
     });
 }
 
-criterion_group!(pareto, bench_mosa);
+/// Graded neighbors of one solution: correlated objectives, a small front
+fn bench_neighbors(c: &mut Criterion) {
+    let dims = 30;
+    let size = 3000;
+
+    let mut data = vec![];
+    let mut rng = rand::rngs::StdRng::from_seed([0u8; 32]);
+
+    for _ in 0..size {
+        let quality = rng.gen::<f32>() * 10.0;
+        let v = (0..dims).map(|_| quality + rng.gen::<f32>()).collect();
+        data.push(MOSA(v));
+    }
+
+    let mut group = c.benchmark_group("neighbors");
+    group.bench_function("kung_recursive", |b| {
+        b.iter(|| black_box(kung_recursive(data.clone())))
+    });
+    group.bench_function("sort_filter", |b| {
+        b.iter(|| black_box(sort_filter(data.clone())))
+    });
+    group.bench_function("best_order_filter", |b| {
+        b.iter(|| black_box(best_order_filter(data.clone())))
+    });
+    group.bench_function("naive", |b| b.iter(|| black_box(naive(data.clone()))));
+    group.finish();
+}
+
+/// Relocations of an initial solution of a converted instance, with its criteria
+fn bench_converted(c: &mut Criterion) {
+    use ntimetable::{neighborhoods::relocation, optimize::Solution, project::Project};
+
+    let project = Project::parse("./converted/comp01");
+    let s = ntimetable::initial::find_initial_solution(&project, false).unwrap();
+    let s = Solution::from_timemap(&s, &project).unwrap();
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    relocation::neighborhoods(s, &project, &tx);
+    drop(tx);
+    let data: Vec<MOSA> = rx
+        .iter()
+        .map(|n| MOSA(project.criteria().evaluate(&n, &project)))
+        .collect();
+
+    let mut group = c.benchmark_group("comp01");
+    group.bench_function("kung_recursive", |b| {
+        b.iter(|| black_box(kung_recursive(data.clone())))
+    });
+    group.bench_function("sort_filter", |b| {
+        b.iter(|| black_box(sort_filter(data.clone())))
+    });
+    group.bench_function("best_order_filter", |b| {
+        b.iter(|| black_box(best_order_filter(data.clone())))
+    });
+    group.bench_function("sort_filter_mosa", |b| {
+        b.iter(|| black_box(sort_filter_mosa(data.clone(), 10.0)))
+    });
+    group.bench_function("best_order_filter_mosa", |b| {
+        b.iter(|| black_box(best_order_filter_mosa(data.clone(), 10.0)))
+    });
+    group.finish();
+}
+
+criterion_group!(pareto, bench_mosa, bench_neighbors, bench_converted);
 criterion_main!(pareto);
@@ -0,0 +1,71 @@
+use super::{normalize, reward_rates, Policy};
+
+/// Probabilities proportional to the reward rates, where a neighborhood whose weight
+/// fell below `penalty_threshold` and now does better than average is pulled back
+/// towards the average over about `penalty_factor` iterations
+pub struct Heuristic {
+    /// Penalised reward rates of the last iteration
+    last: Vec<f32>,
+    penalty_threshold: f32,
+    penalty_factor: f32,
+}
+
+impl Heuristic {
+    pub fn new(initial: Vec<f32>, penalty_threshold: f32, penalty_factor: f32) -> Self {
+        Heuristic {
+            last: initial,
+            penalty_threshold,
+            penalty_factor,
+        }
+    }
+}
+
+impl Policy for Heuristic {
+    fn update(&mut self, novel: &[f32], sizes: &[f32]) {
+        let avg = 1.0 / novel.len() as f32;
+        let decay_constant = self.penalty_threshold / self.penalty_factor;
+        let complementary_factor = avg * decay_constant;
+
+        let rates = reward_rates(novel, sizes);
+        self.last = rates
+            .iter()
+            .zip(&self.last)
+            .map(|(s, last)| {
+                if *s > avg && *last < self.penalty_threshold {
+                    (1.0 - decay_constant) * s + complementary_factor
+                } else {
+                    *s
+                }
+            })
+            .collect();
+    }
+
+    fn probabilities(&self) -> Vec<f32> {
+        let mut p = self.last.clone();
+        normalize(&mut p);
+        p
+    }
+
+    fn weights(&self) -> Vec<f32> {
+        self.last.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_heuristic() {
+        let mut policy = Heuristic::new(vec![0.5, 0.5], 0.1, 2.0);
+        policy.update(&[0.0, 1.0], &[10.0, 10.0]);
+        assert_eq!(policy.probabilities(), vec![0.25, 0.75]);
+
+        // A neighborhood recovering from a low weight is penalised
+        let mut policy = Heuristic::new(vec![0.95, 0.05], 0.1, 2.0);
+        policy.update(&[0.0, 1.0], &[10.0, 10.0]);
+        let weights = policy.weights();
+        assert_eq!(weights[0], 0.25);
+        assert!((weights[1] - (0.95 * 0.75 + 0.025)).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,62 @@
+use super::{relative, reward_rates, Policy};
+
+/// Adaptive pursuit by probability matching: probabilities are proportional to the
+/// average rewards, but never below `min_probability`.
+/// The averages are exponential, with `learning_rate`.
+pub struct ProbabilityMatching {
+    probabilities: Vec<f32>,
+    /// Average reward rate of each neighborhood, relative to the average neighborhood
+    quality: Vec<f32>,
+    learning_rate: f32,
+    min_probability: f32,
+}
+
+impl ProbabilityMatching {
+    pub fn new(initial: Vec<f32>, learning_rate: f32, min_probability: f32) -> Self {
+        // Leave some mass to share according to the rewards
+        let min_probability = min_probability.min(0.5 / initial.len() as f32);
+        ProbabilityMatching {
+            quality: vec![1.0; initial.len()],
+            probabilities: initial,
+            learning_rate,
+            min_probability,
+        }
+    }
+}
+
+impl Policy for ProbabilityMatching {
+    fn update(&mut self, novel: &[f32], sizes: &[f32]) {
+        let rewards = relative(reward_rates(novel, sizes));
+        for (q, r) in self.quality.iter_mut().zip(rewards) {
+            *q += self.learning_rate * (r - *q);
+        }
+
+        let total: f32 = self.quality.iter().sum();
+        let shared = 1.0 - self.quality.len() as f32 * self.min_probability;
+        self.probabilities = self
+            .quality
+            .iter()
+            .map(|q| self.min_probability + shared * q / total)
+            .collect();
+    }
+
+    fn probabilities(&self) -> Vec<f32> {
+        self.probabilities.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_matching() {
+        let mut policy = ProbabilityMatching::new(vec![0.5, 0.5], 1.0, 0.1);
+        policy.update(&[0.0, 1.0], &[1000.0, 1.0]);
+
+        let p = policy.probabilities();
+        assert!((p.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+        assert!(p[0] >= 0.1 && p[0] < 0.11);
+        assert!(p[1] > 0.89);
+    }
+}
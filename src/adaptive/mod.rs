@@ -0,0 +1,114 @@
+// Adaptive selection of the neighborhoods.
+//
+// Each iteration, a neighborhood is rewarded for the solutions it brings into the
+// next population that were never seen before. A policy turns these rewards into
+// the probability of each neighborhood, and `sampling_rates` into the fraction of
+// its neighbors which are graded in the next iteration.
+
+mod heuristic;
+mod matching;
+mod softmax;
+mod ucb;
+
+pub use heuristic::Heuristic;
+pub use matching::ProbabilityMatching;
+pub use softmax::Softmax;
+pub use ucb::Ucb;
+
+use crate::{fatal, project::Project};
+
+/// Reward of a neighborhood for each new solution in the next population
+pub const NOVELTY_REWARD: f32 = 2.0;
+
+pub trait Policy: Send {
+    /// Learn from an iteration where neighborhood `i` generated `sizes[i]` neighbors,
+    /// `novel[i]` of which entered the population for the first time
+    fn update(&mut self, novel: &[f32], sizes: &[f32]);
+
+    /// Probability of each neighborhood, summing to 1
+    fn probabilities(&self) -> Vec<f32>;
+
+    /// Logged as the weights of each iteration
+    fn weights(&self) -> Vec<f32> {
+        self.probabilities()
+    }
+}
+
+/// Policy named by `adaptive_policy` in config.json: "heuristic", "softmax", "ucb" or "matching",
+/// starting from the `initial` probabilities
+pub fn from_config(project: &Project, initial: Vec<f32>) -> Box<dyn Policy> {
+    let config = &project.config;
+
+    match config.adaptive_policy.as_str() {
+        "heuristic" => Box::new(Heuristic::new(
+            initial,
+            config.penalty_threshold,
+            config.penalty_factor,
+        )),
+        "softmax" => Box::new(Softmax::new(
+            initial,
+            config.adaptive_learning_rate,
+            config.adaptive_temperature,
+        )),
+        "ucb" => Box::new(Ucb::new(initial, config.ucb_exploration)),
+        "matching" => Box::new(ProbabilityMatching::new(
+            initial,
+            config.adaptive_learning_rate,
+            config.matching_min_probability,
+        )),
+        _ => fatal!("Invalid adaptive policy: {}", config.adaptive_policy),
+    }
+}
+
+/// Reward per generated neighbor of each neighborhood, normalised to sum to 1.
+/// Every neighborhood gets a base reward of 1, so that none starves.
+pub fn reward_rates(novel: &[f32], sizes: &[f32]) -> Vec<f32> {
+    let mut rates: Vec<f32> = novel
+        .iter()
+        .zip(sizes)
+        .map(|(n, size)| (1.0 + NOVELTY_REWARD * n) / size.max(1.0))
+        .collect();
+    normalize(&mut rates);
+    rates
+}
+
+/// Fraction of the neighbors of each neighborhood to grade, so that about `expected`
+/// neighbors are graded in total
+pub fn sampling_rates(probabilities: &[f32], sizes: &[f32], expected: usize) -> Vec<f32> {
+    let expected_num: f32 = probabilities.iter().zip(sizes).map(|(p, s)| p * s).sum();
+    if expected_num == 0.0 {
+        return probabilities.to_vec();
+    }
+
+    let factor = expected as f32 / expected_num;
+    probabilities.iter().map(|p| p * factor).collect()
+}
+
+/// Scale to sum to 1
+pub fn normalize(x: &mut [f32]) {
+    let sum: f32 = x.iter().sum();
+    for e in x.iter_mut() {
+        *e /= sum;
+    }
+}
+
+/// Rewards relative to the average neighborhood: 1 is average
+fn relative(rates: Vec<f32>) -> Vec<f32> {
+    let len = rates.len() as f32;
+    rates.into_iter().map(|r| r * len).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rates() {
+        // Per generated neighbor, an empty neighborhood counting as one
+        let rates = reward_rates(&[0.0, 1.0, 0.0], &[2.0, 6.0, 0.0]);
+        assert_eq!(rates, vec![0.25, 0.25, 0.5]);
+
+        let rates = sampling_rates(&[0.5, 0.5], &[100.0, 300.0], 100);
+        assert_eq!(rates, vec![0.25, 0.25]);
+    }
+}
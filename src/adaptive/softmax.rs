@@ -0,0 +1,75 @@
+use super::{relative, reward_rates, Policy};
+
+/// Boltzmann exploration: probabilities are the softmax of the average rewards
+/// divided by `temperature`. The averages are exponential, with `learning_rate`.
+pub struct Softmax {
+    probabilities: Vec<f32>,
+    /// Average reward rate of each neighborhood, relative to the average neighborhood
+    quality: Vec<f32>,
+    learning_rate: f32,
+    temperature: f32,
+}
+
+impl Softmax {
+    pub fn new(initial: Vec<f32>, learning_rate: f32, temperature: f32) -> Self {
+        Softmax {
+            quality: vec![1.0; initial.len()],
+            probabilities: initial,
+            learning_rate,
+            temperature,
+        }
+    }
+}
+
+impl Policy for Softmax {
+    fn update(&mut self, novel: &[f32], sizes: &[f32]) {
+        let rewards = relative(reward_rates(novel, sizes));
+        for (q, r) in self.quality.iter_mut().zip(rewards) {
+            *q += self.learning_rate * (r - *q);
+        }
+
+        self.probabilities = self.quality.iter().map(|q| q / self.temperature).collect();
+        softmax_inplace(&mut self.probabilities);
+    }
+
+    fn probabilities(&self) -> Vec<f32> {
+        self.probabilities.clone()
+    }
+}
+
+fn softmax_inplace(x: &mut [f32]) {
+    // Shifted by the maximum so that exp does not overflow
+    let max = x.iter().copied().fold(f32::MIN, f32::max);
+    let mut sum = 0.0;
+    for e in x.iter_mut() {
+        *e = (*e - max).exp();
+        sum += *e;
+    }
+    for e in x.iter_mut() {
+        *e /= sum;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_softmax() {
+        let mut x = vec![1.0, 1.0, 1000.0];
+        softmax_inplace(&mut x);
+        assert_eq!(x, vec![0.0, 0.0, 1.0]);
+
+        let mut policy = Softmax::new(vec![0.5, 0.5], 1.0, 1.0);
+        policy.update(&[0.0, 0.0], &[10.0, 10.0]);
+        assert_eq!(policy.probabilities(), vec![0.5, 0.5]);
+
+        // Colder is greedier
+        policy.update(&[0.0, 1.0], &[10.0, 10.0]);
+        let warm = policy.probabilities();
+        policy.temperature = 0.1;
+        policy.update(&[0.0, 1.0], &[10.0, 10.0]);
+        let cold = policy.probabilities();
+        assert!(warm[1] > 0.5 && cold[1] > warm[1]);
+    }
+}
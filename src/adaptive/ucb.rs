@@ -0,0 +1,86 @@
+use super::{normalize, relative, reward_rates, Policy};
+
+/// Upper confidence bound bandit. All neighborhoods are played each iteration, each
+/// as much as its probability, so that probability is counted as the number of plays.
+/// Probabilities are proportional to the average reward plus
+/// `exploration * sqrt(ln(iterations) / plays)`.
+pub struct Ucb {
+    probabilities: Vec<f32>,
+    /// Average reward rate of each neighborhood, relative to the average neighborhood
+    quality: Vec<f32>,
+    plays: Vec<f32>,
+    iterations: usize,
+    exploration: f32,
+}
+
+impl Ucb {
+    pub fn new(initial: Vec<f32>, exploration: f32) -> Self {
+        Ucb {
+            quality: vec![0.0; initial.len()],
+            plays: vec![0.0; initial.len()],
+            probabilities: initial,
+            iterations: 0,
+            exploration,
+        }
+    }
+}
+
+impl Policy for Ucb {
+    fn update(&mut self, novel: &[f32], sizes: &[f32]) {
+        self.iterations += 1;
+
+        let rewards = relative(reward_rates(novel, sizes));
+        for (((q, n), played), r) in self
+            .quality
+            .iter_mut()
+            .zip(self.plays.iter_mut())
+            .zip(&self.probabilities)
+            .zip(rewards)
+        {
+            *n += played;
+            if *n > 0.0 {
+                *q += played * (r - *q) / *n;
+            }
+        }
+
+        let ln = (self.iterations as f32).ln();
+        self.probabilities = self
+            .quality
+            .iter()
+            .zip(&self.plays)
+            .map(|(q, n)| q + self.exploration * (ln / n.max(f32::EPSILON)).sqrt())
+            .collect();
+        normalize(&mut self.probabilities);
+    }
+
+    fn probabilities(&self) -> Vec<f32> {
+        self.probabilities.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ucb() {
+        let mut policy = Ucb::new(vec![0.5, 0.5], 1.0);
+        for _ in 0..10 {
+            policy.update(&[0.0, 1.0], &[10.0, 10.0]);
+        }
+        let p = policy.probabilities();
+        assert!(p[1] > p[0]);
+
+        // The neglected neighborhood gains as the other stops paying
+        for _ in 0..100 {
+            policy.update(&[0.0, 0.0], &[10.0, 10.0]);
+        }
+        assert!(policy.probabilities()[0] > p[0]);
+
+        // Without exploration, an unrewarded neighborhood is hardly played
+        let mut policy = Ucb::new(vec![0.5, 0.5], 0.0);
+        policy.update(&[0.0, 1.0], &[1000.0, 1.0]);
+        let p = policy.probabilities();
+        assert!(p[0] < 0.01);
+    }
+}
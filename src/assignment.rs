@@ -0,0 +1,199 @@
+// Exact room assignment for the events of one slot
+
+use std::collections::HashMap;
+
+use crate::{
+    optimize::Solution,
+    project::{Event, Person, Project, Room},
+};
+
+/// Cost of an incompatible (event, room) pair
+const INFEASIBLE: i64 = 1 << 40;
+
+/// Minimum cost assignment of rows to distinct columns (Hungarian algorithm).
+/// Requires `rows <= columns`, gives the column of each row.
+pub fn hungarian(cost: &[Vec<i64>]) -> Vec<usize> {
+    let n = cost.len();
+    if n == 0 {
+        return vec![];
+    }
+    let m = cost[0].len();
+    assert!(n <= m, "More rows than columns");
+
+    // 1-indexed, column 0 is virtual
+    let mut u = vec![0; n + 1];
+    let mut v = vec![0; m + 1];
+    let mut p = vec![0; m + 1];
+    let mut way = vec![0; m + 1];
+
+    for i in 1..=n {
+        p[0] = i;
+        let mut j0 = 0;
+        let mut minv = vec![i64::MAX; m + 1];
+        let mut used = vec![false; m + 1];
+
+        loop {
+            used[j0] = true;
+            let i0 = p[j0];
+            let mut delta = i64::MAX;
+            let mut j1 = 0;
+
+            for j in 1..=m {
+                if used[j] {
+                    continue;
+                }
+                let cur = cost[i0 - 1][j - 1] - u[i0] - v[j];
+                if cur < minv[j] {
+                    minv[j] = cur;
+                    way[j] = j0;
+                }
+                if minv[j] < delta {
+                    delta = minv[j];
+                    j1 = j;
+                }
+            }
+
+            for j in 0..=m {
+                if used[j] {
+                    u[p[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    minv[j] -= delta;
+                }
+            }
+
+            j0 = j1;
+            if p[j0] == 0 {
+                break;
+            }
+        }
+
+        loop {
+            let j1 = way[j0];
+            p[j0] = p[j1];
+            j0 = j1;
+            if j0 == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut assignment = vec![0; n];
+    for j in 1..=m {
+        if p[j] != 0 {
+            assignment[p[j] - 1] = j - 1;
+        }
+    }
+    assignment
+}
+
+/// Give each event a room of its kind, minimising `cost(event, room)`.
+/// None if there is no feasible assignment.
+pub fn match_rooms<F: Fn(Event, Room) -> i64>(
+    events: &[Event],
+    project: &Project,
+    cost: F,
+) -> Option<Vec<(Event, Room)>> {
+    if events.len() > project.rooms.len() {
+        return None;
+    }
+
+    let matrix: Vec<Vec<i64>> = events
+        .iter()
+        .map(|e| {
+            project
+                .rooms
+                .iter_all()
+                .map(|r| {
+                    if project.rooms.room_kind(&r) == project.events.room_kind(e) {
+                        cost(*e, r)
+                    } else {
+                        INFEASIBLE
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    let assignment = hungarian(&matrix);
+
+    let mut rooms = vec![];
+    for (i, j) in assignment.into_iter().enumerate() {
+        if matrix[i][j] >= INFEASIBLE {
+            return None;
+        }
+        rooms.push((events[i], Room(j)));
+    }
+
+    Some(rooms)
+}
+
+/// Whether the events can share a slot as far as rooms are concerned
+pub fn rooms_suffice(events: &[Event], project: &Project) -> bool {
+    match_rooms(events, project, |_, _| 0).is_some()
+}
+
+/// Rooms of the closest events before and after slot `t` on the same day, for each person
+fn adjacent_rooms(s: &Solution, t: usize, project: &Project) -> HashMap<Person, Vec<Room>> {
+    let mut before: HashMap<Person, (usize, Room)> = HashMap::new();
+    let mut after: HashMap<Person, (usize, Room)> = HashMap::new();
+
+    for (t2, e, r) in s.events_of_day(project.config.slots_to_day(t), project) {
+        for p in project.events.event_attendees(e) {
+            if t2 < t {
+                let entry = before.entry(*p).or_insert((t2, r));
+                if t2 >= entry.0 {
+                    *entry = (t2, r);
+                }
+            } else if t2 > t {
+                let entry = after.entry(*p).or_insert((t2, r));
+                if t2 <= entry.0 {
+                    *entry = (t2, r);
+                }
+            }
+        }
+    }
+
+    let mut rooms: HashMap<Person, Vec<Room>> = HashMap::new();
+    for (p, (_, r)) in before.into_iter().chain(after) {
+        rooms.entry(p).or_default().push(r);
+    }
+    rooms
+}
+
+/// Rooms for the events of slot `t`, minimising the distance attendees walk
+/// from and to their adjacent events of the day
+pub fn assign_slot(s: &Solution, t: usize, project: &Project) -> Option<Vec<(Event, Room)>> {
+    let adjacent = adjacent_rooms(s, t, project);
+    let events: Vec<Event> = s.events_in_slot(t).map(|(e, _)| e).collect();
+
+    match_rooms(&events, project, |e, r| {
+        project
+            .events
+            .event_attendees(e)
+            .iter()
+            .filter_map(|p| adjacent.get(p))
+            .flatten()
+            .map(|r2| project.rooms.distance(r2, &r) as i64)
+            .sum()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hungarian() {
+        let cost = vec![vec![4, 1, 3], vec![2, 0, 5], vec![3, 2, 2]];
+        let a = hungarian(&cost);
+        let total: i64 = a.iter().enumerate().map(|(i, j)| cost[i][*j]).sum();
+        assert_eq!(total, 5);
+
+        // Rectangular
+        let cost = vec![vec![7, 3, 9, 1], vec![2, 8, 1, 9]];
+        assert_eq!(hungarian(&cost), vec![3, 2]);
+
+        assert!(hungarian(&[]).is_empty());
+    }
+}
@@ -2,21 +2,16 @@ use std::collections::HashSet;
 
 use serde::Deserialize;
 
-use crate::{fatal, project::Event};
+use crate::project::Event;
 
-use super::{Criterion, CriterionT};
-
-fn default_weight() -> f32 {
-    1.0
-}
+use super::{Criterion, CriterionT, Objective};
 
 #[derive(Debug, Deserialize)]
 pub struct EventTimeDistance {
-    #[serde(default = "default_weight")]
-    weight: f32,
     event: String,
     time: usize,
-    kind: String,
+    #[serde(flatten)]
+    objective: Objective,
 
     #[serde(skip)]
     events_set: HashSet<Event>,
@@ -31,6 +26,10 @@ impl CriterionT for EventTimeDistance {
         );
     }
 
+    fn objective(&self) -> Objective {
+        self.objective.inverted_weight()
+    }
+
     fn evaluate(&self, s: &crate::optimize::Solution, project: &crate::project::Project) -> f32 {
         let mut score = 0.0;
         for d in project.config.days() {
@@ -41,22 +40,32 @@ impl CriterionT for EventTimeDistance {
             }
         }
 
-        if self.kind == "max" {
-            -score / self.weight
-        } else {
-            score / self.weight
+        score
+    }
+
+    fn linear(&self, e: Event, t: usize, _project: &crate::project::Project) -> Option<f32> {
+        if !self.events_set.contains(&e) {
+            return Some(0.0);
         }
+
+        Some((t as f32 - self.time as f32).powi(2))
     }
 }
 
-pub fn parse(config: &str) -> Criterion {
-    if let Ok(e) = serde_json::from_str::<EventTimeDistance>(config) {
-        Criterion::EventTimeDistance(e)
-    } else {
-        fatal!("Failed to parse event_time_distance criterion")
-    }
+pub fn parse(config: &str) -> Result<Criterion, String> {
+    serde_json::from_str::<EventTimeDistance>(config)
+        .map(Criterion::EventTimeDistance)
+        .map_err(|e| e.to_string())
 }
 
 mod test {
     use super::*;
+
+    #[test]
+    fn test_weight_divides() {
+        let c: EventTimeDistance =
+            serde_json::from_str(r#"{"kind": "max", "event": "a", "time": 0, "weight": 2}"#)
+                .unwrap();
+        assert_eq!(c.objective().score(4.0), -2.0);
+    }
 }
@@ -2,20 +2,15 @@ use std::collections::HashSet;
 
 use serde::Deserialize;
 
-use crate::{fatal, project::Event};
+use crate::project::Event;
 
-use super::{Criterion, CriterionT};
-
-fn default_weight() -> f32 {
-    1.0
-}
+use super::{Criterion, CriterionT, Objective};
 
 #[derive(Debug, Deserialize)]
 pub struct EventsDistance {
-    #[serde(default = "default_weight")]
-    weight: f32,
     events: Vec<String>,
-    kind: String,
+    #[serde(flatten)]
+    objective: Objective,
 
     #[serde(skip)]
     events_set: HashSet<Event>,
@@ -32,6 +27,10 @@ impl CriterionT for EventsDistance {
         }
     }
 
+    fn objective(&self) -> Objective {
+        self.objective.inverted_weight()
+    }
+
     fn evaluate(&self, s: &crate::optimize::Solution, project: &crate::project::Project) -> f32 {
         let mut score = 0.0;
         for d in project.config.days() {
@@ -50,19 +49,14 @@ impl CriterionT for EventsDistance {
             }
         }
 
-        if self.kind == "max" {
-            -score / self.weight
-        } else {
-            score / self.weight
-        }
+        score
     }
 }
 
 pub struct EventsDistanceSameType {
-    weight: f32,
     event: String,
     events: HashSet<Event>,
-    kind: String,
+    objective: Objective,
 }
 
 impl CriterionT for EventsDistanceSameType {
@@ -72,6 +66,10 @@ impl CriterionT for EventsDistanceSameType {
             .events_with_kind(project.events.kind_name_to_id(&self.event));
     }
 
+    fn objective(&self) -> Objective {
+        self.objective.inverted_weight()
+    }
+
     fn evaluate(&self, s: &crate::optimize::Solution, project: &crate::project::Project) -> f32 {
         let mut score = 0.0;
         for d in project.config.days() {
@@ -88,40 +86,31 @@ impl CriterionT for EventsDistanceSameType {
             }
         }
 
-        if self.kind == "max" {
-            -score / self.weight
-        } else {
-            score / self.weight
-        }
+        score
     }
 }
 
-pub fn parse(config: &str) -> Criterion {
-    if let Ok(e) = serde_json::from_str::<EventsDistance>(config) {
-        let mut events = e.events;
+pub fn parse(config: &str) -> Result<Criterion, String> {
+    let e = serde_json::from_str::<EventsDistance>(config).map_err(|e| e.to_string())?;
+    let mut events = e.events;
 
-        if events.len() == 0 {
-            fatal!("Criterion events_distance need at least one event");
-        }
+    if events.len() == 0 {
+        return Err("at least one event is needed".to_owned());
+    }
 
-        events.dedup();
-
-        if events.len() == 1 {
-            Criterion::EventsDistanceSameType(EventsDistanceSameType {
-                weight: e.weight,
-                event: events[0].clone(),
-                events: HashSet::new(),
-                kind: e.kind,
-            })
-        } else {
-            Criterion::EventsDistance(EventsDistance {
-                weight: e.weight,
-                events,
-                events_set: HashSet::new(),
-                kind: e.kind,
-            })
-        }
+    events.dedup();
+
+    if events.len() == 1 {
+        Ok(Criterion::EventsDistanceSameType(EventsDistanceSameType {
+            event: events[0].clone(),
+            events: HashSet::new(),
+            objective: e.objective,
+        }))
     } else {
-        fatal!("Failed to parse events_distance criterion")
+        Ok(Criterion::EventsDistance(EventsDistance {
+            events,
+            events_set: HashSet::new(),
+            objective: e.objective,
+        }))
     }
 }
@@ -1,6 +1,8 @@
 mod event_time_distance;
 mod events_distance;
+mod normalize;
 mod room_distance;
+mod script;
 
 use std::{collections::HashMap, path::Path};
 
@@ -8,43 +10,464 @@ use enum_dispatch::enum_dispatch;
 use serde::Deserialize;
 use serde_json::value::RawValue;
 
-use crate::{fatal, must_open, optimize::Solution, project::Project, warn};
+pub use normalize::Normalization;
+
+use crate::{
+    fatal, must_open,
+    optimize::Solution,
+    project::{Event, Project},
+    warn,
+};
 
 #[enum_dispatch]
-enum Criterion {
+pub enum Criterion {
     RoomDistance(room_distance::RoomDistance),
     EventsDistance(events_distance::EventsDistance),
     EventsDistanceSameType(events_distance::EventsDistanceSameType),
     EventTimeDistance(event_time_distance::EventTimeDistance),
+    Script(script::Script),
+    /// Criterion defined outside of this crate
+    Custom(Box<dyn CriterionT>),
+}
+
+impl Criterion {
+    /// Wrap a user-defined criterion
+    pub fn custom<C: CriterionT + 'static>(c: C) -> Self {
+        Criterion::Custom(Box::new(c))
+    }
+}
+
+/// Whether the value of a criterion should be as small or as large as possible
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Minimize,
+    #[default]
+    Maximize,
+}
+
+impl Direction {
+    /// Score of a value, higher scores are better
+    pub fn score(self, value: f32) -> f32 {
+        match self {
+            Direction::Minimize => -value,
+            Direction::Maximize => value,
+        }
+    }
 }
 
+/// How a criterion is optimized, shared by the entries of criteria.json:
+/// - `"direction"`: `"minimize"` or `"maximize"`
+/// - `"weight"`: factor of the score, 1 by default. `event_time_distance` and
+///   `events_distance` divide their score by it instead, as they always did
+///   (see `Objective::inverted_weight`)
+/// - `"epsilon"`: difference of scores below which solutions are not told apart
+///   (see `pareto::epsilon_dominates`)
+/// - `"range"`: `[min, max]` of the value, used for normalisation instead of
+///   the values seen during the run
+///
+/// The older `"kind": "max"` (maximal closeness) stands for `"direction": "minimize"`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(from = "RawObjective")]
+pub struct Objective {
+    pub direction: Direction,
+    pub weight: f32,
+    pub epsilon: f32,
+    pub range: Option<(f32, f32)>,
+    /// The value is divided by `weight` instead of multiplied
+    pub inverted: bool,
+}
+
+impl Default for Objective {
+    fn default() -> Self {
+        Objective {
+            direction: Direction::default(),
+            weight: 1.0,
+            epsilon: 0.0,
+            range: None,
+            inverted: false,
+        }
+    }
+}
+
+impl Objective {
+    /// Score of a value of the criterion, higher is better
+    pub fn score(&self, value: f32) -> f32 {
+        self.direction.score(value) * self.factor()
+    }
+
+    /// What the value is multiplied by in the score
+    pub fn factor(&self) -> f32 {
+        if self.inverted {
+            1.0 / self.weight
+        } else {
+            self.weight
+        }
+    }
+
+    /// The same objective, dividing the value by the weight
+    pub fn inverted_weight(self) -> Self {
+        Objective {
+            inverted: true,
+            ..self
+        }
+    }
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+#[derive(Deserialize)]
+struct RawObjective {
+    direction: Option<Direction>,
+    kind: Option<String>,
+    #[serde(default = "default_weight")]
+    weight: f32,
+    #[serde(default)]
+    epsilon: f32,
+    range: Option<(f32, f32)>,
+}
+
+impl From<RawObjective> for Objective {
+    fn from(raw: RawObjective) -> Self {
+        let direction = match (raw.direction, raw.kind.as_deref()) {
+            (Some(d), _) => d,
+            (None, Some("max")) => Direction::Minimize,
+            (None, _) => Direction::Maximize,
+        };
+
+        Objective {
+            direction,
+            weight: raw.weight,
+            epsilon: raw.epsilon,
+            range: raw.range,
+            inverted: false,
+        }
+    }
+}
+
+/// An objective of the optimization
 #[enum_dispatch(Criterion)]
-trait CriterionT {
+pub trait CriterionT: Send + Sync {
+    /// Value of the criterion, to be minimized or maximized according to `objective`
     fn evaluate(&self, s: &Solution, project: &Project) -> f32;
 
+    fn objective(&self) -> Objective {
+        Objective::default()
+    }
+
+    /// Called once after all criteria are parsed
     fn init(&mut self, _project: &Project) {}
+
+    /// Contribution of event `e` placed at slot `t`, if the score is a sum of such contributions.
+    /// Used to build linear models.
+    fn linear(&self, _e: Event, _t: usize, _project: &Project) -> Option<f32> {
+        None
+    }
+}
+
+impl CriterionT for Box<dyn CriterionT> {
+    fn evaluate(&self, s: &Solution, project: &Project) -> f32 {
+        self.as_ref().evaluate(s, project)
+    }
+
+    fn objective(&self) -> Objective {
+        self.as_ref().objective()
+    }
+
+    fn init(&mut self, project: &Project) {
+        self.as_mut().init(project)
+    }
+
+    fn linear(&self, e: Event, t: usize, project: &Project) -> Option<f32> {
+        self.as_ref().linear(e, t, project)
+    }
+}
+
+/// How the scores of the criteria of a named objective are combined
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Aggregate {
+    /// Sum of the scores
+    #[default]
+    Sum,
+    /// The worst score, i.e. the largest penalty
+    Max,
+    /// Mean of the scores, weighted by the criteria weights of criteria.json
+    Weighted,
+}
+
+impl Aggregate {
+    fn apply(self, scores: impl Iterator<Item = (f32, f32)>) -> f32 {
+        let (mut sum, mut worst, mut weights) = (0.0, f32::MAX, 0.0);
+        for (score, weight) in scores {
+            sum += score;
+            worst = worst.min(score);
+            weights += weight;
+        }
+
+        match self {
+            Aggregate::Sum => sum,
+            Aggregate::Max => worst,
+            Aggregate::Weighted if weights != 0.0 => sum / weights,
+            Aggregate::Weighted => 0.0,
+        }
+    }
+}
+
+/// An objective of the optimization made of criteria, declared in the
+/// `"objectives"` list of criteria.json. Criteria join it with `"objective": name`.
+/// `weight`, `epsilon` and `range` apply to the aggregated score and default
+/// to those of a single criterion.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NamedObjective {
+    pub name: String,
+    #[serde(default)]
+    pub aggregate: Aggregate,
+    pub weight: Option<f32>,
+    pub epsilon: Option<f32>,
+    pub range: Option<(f32, f32)>,
+
+    /// Indices of the criteria
+    #[serde(skip)]
+    pub members: Vec<usize>,
 }
 
-pub struct Criteria(Vec<Criterion>);
+impl NamedObjective {
+    fn new(name: String) -> Self {
+        NamedObjective {
+            name,
+            aggregate: Aggregate::default(),
+            weight: None,
+            epsilon: None,
+            range: None,
+            members: vec![],
+        }
+    }
+}
+
+pub struct Criteria {
+    criteria: Vec<Criterion>,
+    /// Name of each criterion in reports
+    labels: Vec<String>,
+    objectives: Vec<NamedObjective>,
+}
 
 impl Criteria {
+    /// One objective per criterion, in order
+    pub fn new(criteria: Vec<Criterion>) -> Self {
+        let names = vec![None; criteria.len()];
+        Criteria::with_objectives(criteria, &names, vec![])
+    }
+
+    /// Criteria naming an objective in `objective` join it, the others are an objective on their own.
+    /// Objectives which are not `declared` sum their criteria.
+    pub fn with_objectives(
+        criteria: Vec<Criterion>,
+        objective: &[Option<String>],
+        declared: Vec<NamedObjective>,
+    ) -> Self {
+        let mut objectives = declared;
+
+        for (i, name) in objective.iter().enumerate() {
+            let existing = name
+                .as_ref()
+                .and_then(|n| objectives.iter_mut().find(|o| o.name == *n));
+
+            match (existing, name) {
+                (Some(o), _) => o.members.push(i),
+                (None, name) => {
+                    let name = name.clone().unwrap_or_else(|| i.to_string());
+                    let mut o = NamedObjective::new(name);
+                    o.members.push(i);
+                    objectives.push(o);
+                }
+            }
+        }
+
+        objectives.retain(|o| {
+            if o.members.is_empty() {
+                warn!("Objective {} has no criteria", o.name);
+            }
+            !o.members.is_empty()
+        });
+
+        Criteria {
+            labels: (0..criteria.len()).map(|i| i.to_string()).collect(),
+            criteria,
+            objectives,
+        }
+    }
+
+    /// Score of each criterion, higher is better
+    pub fn evaluate_criteria(&self, s: &Solution, project: &Project) -> Vec<f32> {
+        self.criteria
+            .iter()
+            .map(|c| c.objective().score(c.evaluate(s, project)))
+            .collect()
+    }
+
+    /// Score of each objective, higher is better
     pub fn evaluate(&self, s: &Solution, project: &Project) -> Vec<f32> {
-        self.0.iter().map(|c| c.evaluate(&s, &project)).collect()
+        self.aggregate(&self.evaluate_criteria(s, project))
+    }
+
+    /// Objective scores from the scores of the criteria
+    pub fn aggregate(&self, scores: &[f32]) -> Vec<f32> {
+        self.objectives
+            .iter()
+            .map(|o| {
+                o.aggregate.apply(
+                    o.members
+                        .iter()
+                        .map(|i| (scores[*i], self.criteria[*i].objective().weight)),
+                )
+            })
+            .collect()
+    }
+
+    /// Resolution of each objective, by default the largest one of its criteria.
+    /// See `pareto::epsilon_dominates`
+    pub fn epsilons(&self) -> Vec<f32> {
+        self.objectives
+            .iter()
+            .map(|o| {
+                o.epsilon.unwrap_or_else(|| {
+                    o.members
+                        .iter()
+                        .map(|i| self.criteria[*i].objective().epsilon)
+                        .fold(0.0, f32::max)
+                })
+            })
+            .collect()
+    }
+
+    /// Bounds of each objective score, if declared or if every criterion of it declares a range
+    pub fn ranges(&self) -> Vec<Option<(f32, f32)>> {
+        self.objectives
+            .iter()
+            .map(|o| {
+                if o.range.is_some() {
+                    return o.range;
+                }
+
+                let bounds: Option<Vec<((f32, f32), f32)>> = o
+                    .members
+                    .iter()
+                    .map(|i| {
+                        let c = self.criteria[*i].objective();
+                        let (a, b) = c.range?;
+                        let (a, b) = (c.score(a), c.score(b));
+                        Some(((a.min(b), a.max(b)), c.weight))
+                    })
+                    .collect();
+                let bounds = bounds?;
+
+                let lo = o
+                    .aggregate
+                    .apply(bounds.iter().map(|((lo, _), w)| (*lo, *w)));
+                let hi = o
+                    .aggregate
+                    .apply(bounds.iter().map(|((_, hi), w)| (*hi, *w)));
+                Some((lo, hi))
+            })
+            .collect()
+    }
+
+    /// Weight of each objective after normalisation: the declared one, or the weight
+    /// of a single criterion, 1 for aggregates of already weighted criteria
+    pub fn weights(&self) -> Vec<f32> {
+        self.objectives
+            .iter()
+            .map(|o| {
+                o.weight.unwrap_or_else(|| match o.members.as_slice() {
+                    [i] => self.criteria[*i].objective().factor(),
+                    _ => 1.0,
+                })
+            })
+            .collect()
     }
 
     pub fn init(&mut self, project: &Project) {
-        self.0.iter_mut().for_each(|c| c.init(&project));
+        self.criteria.iter_mut().for_each(|c| c.init(project));
     }
 
+    /// Number of objectives
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.objectives.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objectives.is_empty()
+    }
+
+    pub fn objectives(&self) -> &[NamedObjective] {
+        &self.objectives
+    }
+
+    /// Name of each criterion, `type[index]` as in criteria.json
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    /// Every criterion, ungrouped
+    pub fn iter(&self) -> impl Iterator<Item = &Criterion> {
+        self.criteria.iter()
+    }
+}
+
+/// Parses one entry of criteria.json (as raw json) into a criterion
+pub type CriterionParser = Box<dyn Fn(&str) -> Result<Criterion, String> + Send + Sync>;
+
+/// Maps criterion types in criteria.json to their parsers
+pub struct Registry(HashMap<String, CriterionParser>);
+
+impl Default for Registry {
+    fn default() -> Self {
+        let mut r = Registry(HashMap::new());
+        r.register("room_distance", room_distance::parse);
+        r.register("events_distance", events_distance::parse);
+        r.register("event_time_distance", event_time_distance::parse);
+        r.register("script", script::parse);
+        r
+    }
+}
+
+impl Registry {
+    /// Register a criterion type. Existing types with the same name are replaced.
+    pub fn register<F>(&mut self, name: &str, parser: F) -> &mut Self
+    where
+        F: Fn(&str) -> Result<Criterion, String> + Send + Sync + 'static,
+    {
+        self.0.insert(name.to_owned(), Box::new(parser));
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CriterionParser> {
+        self.0.get(name)
+    }
+
+    /// Parse an entry of criteria.json of type `name`, the error names the type
+    pub fn parse(&self, name: &str, config: &str) -> Result<Criterion, String> {
+        let parser = self
+            .get(name)
+            .ok_or_else(|| format!("unknown criterion type {name}"))?;
+        parser(config).map_err(|e| format!("{name}: {e}"))
     }
 }
 
 #[derive(Deserialize)]
 struct RawCriteria(HashMap<String, Vec<Box<RawValue>>>);
 
-pub fn parse_criteria<P: AsRef<Path>>(path: P, project: &Project) -> Criteria {
+/// Fields of every entry of criteria.json handled by `Criteria`
+#[derive(Deserialize)]
+struct Entry {
+    #[serde(alias = "group")]
+    objective: Option<String>,
+}
+
+pub fn parse_criteria<P: AsRef<Path>>(path: P, project: &Project, registry: &Registry) -> Criteria {
     let path = path.as_ref();
     let criteria_json = must_open!(path, "criteria.json");
 
@@ -52,26 +475,165 @@ pub fn parse_criteria<P: AsRef<Path>>(path: P, project: &Project) -> Criteria {
     if let Err(e) = criteria {
         fatal!("Failed to parse events.json: {e}");
     }
-    let criteria = criteria.unwrap().0;
+    let mut criteria = criteria.unwrap().0;
+
+    let declared: Vec<NamedObjective> = criteria
+        .remove("objectives")
+        .unwrap_or_default()
+        .iter()
+        .map(|r| match serde_json::from_str(r.get()) {
+            Ok(o) => o,
+            Err(e) => fatal!("Failed to parse objective: {e}"),
+        })
+        .collect();
 
     let mut boxed_criteria = vec![];
+    let mut labels = vec![];
+    let mut objective = vec![];
     for (k, v) in criteria.iter() {
-        let parser = match k.as_str() {
-            // "event_time_distance" =>
-            "room_distance" => room_distance::parse,
-            "events_distance" => events_distance::parse,
-            "event_time_distance" => event_time_distance::parse,
-            _ => {
-                warn!("Invalid criterion type: {k}");
-                continue;
-            }
-        };
+        if registry.get(k).is_none() {
+            warn!("Invalid criterion type: {k}");
+            continue;
+        }
 
-        boxed_criteria.extend(v.iter().map(|r| parser(&r.to_string())));
+        for (i, r) in v.iter().enumerate() {
+            let entry = serde_json::from_str::<Entry>(r.get());
+            objective.push(entry.map(|e| e.objective).unwrap_or_default());
+            labels.push(format!("{k}[{i}]"));
+            match registry.parse(k, r.get()) {
+                Ok(c) => boxed_criteria.push(c),
+                Err(e) => fatal!("Failed to parse criterion {k}[{i}]: {e}"),
+            }
+        }
     }
 
-    let mut c = Criteria(boxed_criteria);
+    let mut c = Criteria::with_objectives(boxed_criteria, &objective, declared);
+    c.labels = labels;
     c.init(project);
 
     c
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_named_objectives() {
+        let criteria = (0..4).map(|_| Criterion::custom(Constant(1.0))).collect();
+        let a = Some("a".to_owned());
+        let b = Some("b".to_owned());
+        let declared: NamedObjective =
+            serde_json::from_str(r#"{"name": "b", "aggregate": "weighted", "epsilon": 2}"#)
+                .unwrap();
+        let criteria =
+            Criteria::with_objectives(criteria, &[a.clone(), b.clone(), None, b], vec![declared]);
+
+        let names: Vec<&str> = criteria
+            .objectives()
+            .iter()
+            .map(|o| o.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["b", "a", "2"]);
+        assert_eq!(criteria.objectives()[0].members, vec![1, 3]);
+        assert_eq!(criteria.epsilons(), vec![2.0, 0.0, 0.0]);
+
+        let project = Project::parse("./converted/comp01");
+        let s = Solution::empty(&project);
+        assert_eq!(criteria.evaluate(&s, &project), vec![1.0, 1.0, 1.0]);
+
+        assert_eq!(
+            Aggregate::Sum.apply([(1.0, 1.0), (-3.0, 1.0)].into_iter()),
+            -2.0
+        );
+        assert_eq!(
+            Aggregate::Max.apply([(1.0, 1.0), (-3.0, 1.0)].into_iter()),
+            -3.0
+        );
+        assert_eq!(
+            Aggregate::Weighted.apply([(2.0, 1.0), (4.0, 3.0)].into_iter()),
+            1.5
+        );
+    }
+
+    #[test]
+    fn test_parse_objective() {
+        let parse = |s: &str| serde_json::from_str::<Objective>(s).unwrap();
+
+        assert_eq!(parse("{}").direction, Direction::Maximize);
+        assert_eq!(parse(r#"{"kind": "max"}"#).direction, Direction::Minimize);
+        assert_eq!(parse(r#"{"kind": "min"}"#).direction, Direction::Maximize);
+
+        let o = parse(r#"{"kind": "max", "direction": "maximize", "epsilon": 0.5}"#);
+        assert_eq!(o.direction, Direction::Maximize);
+        assert_eq!(o.epsilon, 0.5);
+    }
+
+    struct Constant(f32);
+
+    impl CriterionT for Constant {
+        fn evaluate(&self, _s: &Solution, _project: &Project) -> f32 {
+            self.0
+        }
+    }
+
+    struct Weighted(f32, Objective);
+
+    impl CriterionT for Weighted {
+        fn evaluate(&self, _s: &Solution, _project: &Project) -> f32 {
+            self.0
+        }
+
+        fn objective(&self) -> Objective {
+            self.1
+        }
+    }
+
+    #[test]
+    fn test_inverted_weight() {
+        let o: Objective = serde_json::from_str(r#"{"weight": 4}"#).unwrap();
+        assert_eq!(o.score(8.0), 32.0);
+        let inverted = o.inverted_weight();
+        assert_eq!(inverted.weight, 4.0);
+        assert_eq!(inverted.score(8.0), 2.0);
+
+        // The weighted mean is over the weights of criteria.json
+        let criteria = vec![
+            Criterion::custom(Weighted(8.0, inverted)),
+            Criterion::custom(Weighted(1.0, Objective::default())),
+        ];
+        let declared: NamedObjective =
+            serde_json::from_str(r#"{"name": "a", "aggregate": "weighted"}"#).unwrap();
+        let a = Some("a".to_owned());
+        let criteria = Criteria::with_objectives(criteria, &[a.clone(), a], vec![declared]);
+        assert_eq!(criteria.aggregate(&[2.0, 1.0]), vec![3.0 / 5.0]);
+    }
+
+    #[test]
+    fn test_register_custom_criterion() {
+        let mut registry = Registry::default();
+        assert!(registry.get("room_distance").is_some());
+        assert!(registry.get("constant").is_none());
+
+        // Parsers may capture state and report errors
+        let value = 2.0;
+        registry.register("constant", move |s| match s {
+            "{}" => Ok(Criterion::custom(Constant(value))),
+            _ => Err("no options expected".to_owned()),
+        });
+        assert!(matches!(
+            registry.parse("constant", "{}"),
+            Ok(Criterion::Custom(_))
+        ));
+        assert_eq!(
+            registry.parse("constant", "[]").err().unwrap(),
+            "constant: no options expected"
+        );
+        assert!(registry.parse("nope", "{}").is_err());
+        assert!(registry
+            .parse("room_distance", r#"{"direction": "maximize"}"#)
+            .err()
+            .unwrap()
+            .starts_with("room_distance"));
+    }
+}
@@ -0,0 +1,96 @@
+use super::Criteria;
+
+/// Maps objective scores to [0, 1] from the worst (nadir) to the best (ideal)
+/// score, times the weight of the objective. Objectives without a fixed range
+/// track the best and worst scores seen so far.
+#[derive(Debug, Clone)]
+pub struct Normalization {
+    ideal: Vec<f32>,
+    nadir: Vec<f32>,
+    fixed: Vec<bool>,
+    weights: Vec<f32>,
+}
+
+impl Normalization {
+    pub fn new(criteria: &Criteria) -> Self {
+        let ranges = criteria.ranges();
+
+        Normalization {
+            ideal: ranges
+                .iter()
+                .map(|r| r.map(|r| r.1).unwrap_or(f32::MIN))
+                .collect(),
+            nadir: ranges
+                .iter()
+                .map(|r| r.map(|r| r.0).unwrap_or(f32::MAX))
+                .collect(),
+            fixed: ranges.iter().map(|r| r.is_some()).collect(),
+            weights: criteria.weights(),
+        }
+    }
+
+    /// Widen the tracked ranges to include `scores`
+    pub fn observe(&mut self, scores: &[f32]) {
+        for (i, s) in scores.iter().enumerate() {
+            if !self.fixed[i] {
+                self.ideal[i] = self.ideal[i].max(*s);
+                self.nadir[i] = self.nadir[i].min(*s);
+            }
+        }
+    }
+
+    pub fn apply(&self, scores: &[f32]) -> Vec<f32> {
+        scores
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let range = self.ideal[i] - self.nadir[i];
+                if range > 0.0 {
+                    self.weights[i] * (s - self.nadir[i]) / range
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::criteria::{Criterion, CriterionT, Objective};
+    use crate::{optimize::Solution, project::Project};
+
+    struct Fixed(Objective);
+
+    impl CriterionT for Fixed {
+        fn evaluate(&self, _s: &Solution, _project: &Project) -> f32 {
+            0.0
+        }
+
+        fn objective(&self) -> Objective {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_normalization() {
+        let ranged = Objective {
+            weight: 2.0,
+            range: Some((0.0, 10.0)),
+            ..Default::default()
+        };
+        let criteria = Criteria::new(vec![
+            Criterion::custom(Fixed(ranged)),
+            Criterion::custom(Fixed(Objective::default())),
+        ]);
+
+        let mut n = Normalization::new(&criteria);
+        n.observe(&[100.0, -4.0]);
+        n.observe(&[-100.0, 4.0]);
+
+        // The fixed range, of weighted scores, is kept; the other one is tracked
+        assert_eq!(n.apply(&[10.0, 0.0]), vec![1.0, 0.5]);
+        assert_eq!(n.apply(&[20.0, 4.0]), vec![2.0, 1.0]);
+    }
+}
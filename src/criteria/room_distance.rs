@@ -4,24 +4,30 @@ use itertools::Itertools;
 use serde::Deserialize;
 
 use crate::{
-    fatal,
     optimize::Solution,
     project::{Event, Project},
 };
 
-use super::{Criterion, CriterionT};
-
-fn default_weight() -> f32 {
-    1.0
-}
+use super::{Criterion, CriterionT, Direction, Objective};
 
 #[derive(Debug, Deserialize)]
 pub struct RoomDistance {
-    #[serde(default = "default_weight")]
-    weight: f32,
+    /// A distance to walk can only be minimized
+    #[serde(default)]
+    direction: Option<Direction>,
+    #[serde(flatten)]
+    objective: Objective,
 }
 
 impl CriterionT for RoomDistance {
+    fn objective(&self) -> Objective {
+        // Rejected at parse time if configured otherwise
+        Objective {
+            direction: Direction::Minimize,
+            ..self.objective
+        }
+    }
+
     fn evaluate(&self, s: &Solution, project: &Project) -> f32 {
         let mut score = 0;
 
@@ -54,16 +60,17 @@ impl CriterionT for RoomDistance {
             }
         }
 
-        -score as f32 * self.weight
+        score as f32
     }
 }
 
-pub fn parse(config: &str) -> Criterion {
-    // dbg!(config);
-    if let Ok(e) = serde_json::from_str(config) {
-        Criterion::RoomDistance(e)
-    } else {
-        fatal!("Failed to parse room_distance criterion")
+pub fn parse(config: &str) -> Result<Criterion, String> {
+    match serde_json::from_str::<RoomDistance>(config) {
+        Ok(e) if e.direction == Some(Direction::Maximize) => {
+            Err("a distance can only be minimized".to_owned())
+        }
+        Ok(e) => Ok(Criterion::RoomDistance(e)),
+        Err(e) => Err(e.to_string()),
     }
 }
 
@@ -72,14 +79,29 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn test_parse_direction() {
+        let c: RoomDistance =
+            serde_json::from_str(r#"{"direction": "maximize", "weight": 2}"#).unwrap();
+        assert_eq!(c.direction, Some(Direction::Maximize));
+        assert_eq!(c.objective.weight, 2.0);
+
+        let c: RoomDistance = serde_json::from_str("{}").unwrap();
+        assert_eq!(c.direction, None);
+        assert_eq!(c.objective().direction, Direction::Minimize);
+    }
+
     #[test]
     fn test_criterion_room_distance() {
         // return;
         let project = Project::parse("./demo");
 
-        let c = RoomDistance { weight: 1.0 };
+        let c = RoomDistance {
+            direction: None,
+            objective: Objective::default(),
+        };
         let s = crate::initial::find_initial_solution(&project, true);
-        let s = Solution::new(s.unwrap());
+        let s = Solution::from_timemap(&s.unwrap(), &project).unwrap();
         let original_score = c.evaluate(&s, &project);
         dbg!(original_score);
 
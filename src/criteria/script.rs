@@ -0,0 +1,678 @@
+// A tiny expression language for one-off soft rules
+//
+// Expressions are arithmetic over numbers and aggregates of the scheduled events:
+//
+//   sum(filter, expr)   sum `expr` over every event matching `filter`
+//   count(filter)       number of events matching `filter`
+//   days(expr)          sum `expr` over each day, aggregates inside only see that day
+//
+// Inside an aggregate, the current event is described by `slot` (offset in day),
+// `day`, `time` (absolute slot) and can be matched with `kind == "Math"`,
+// `person == "Alice"`, `room == "101"` or `room_kind == "Lab"`.
+// Comparisons and `and` / `or` / `not` yield 1 or 0.
+// Other functions: abs(x), max(a, b), min(a, b), pow(a, b).
+// Arithmetic without a finite result, like a division by zero, gives 0.
+//
+// Like every criterion, a script is maximized unless told otherwise.
+// Example: penalise Math after slot 6
+//   {"expr": "sum(kind == \"Math\" and slot > 6, slot - 6)", "direction": "minimize"}
+
+use serde::Deserialize;
+
+use crate::{
+    fatal,
+    optimize::Solution,
+    project::{Event, EventKind, Person, Project, Room, RoomKind},
+};
+
+use super::{Criterion, CriterionT, Objective};
+
+#[derive(Debug, Deserialize)]
+pub struct Script {
+    expr: String,
+    #[serde(flatten)]
+    objective: Objective,
+
+    #[serde(skip)]
+    compiled: Option<Expr>,
+}
+
+impl CriterionT for Script {
+    fn init(&mut self, project: &Project) {
+        match compile(&self.expr, project) {
+            Ok(e) => self.compiled = Some(e),
+            Err(e) => fatal!("Failed to compile script `{}`: {e}", self.expr),
+        }
+    }
+
+    fn objective(&self) -> Objective {
+        self.objective
+    }
+
+    fn evaluate(&self, s: &Solution, project: &Project) -> f32 {
+        let ctx = Context {
+            solution: s,
+            project,
+            day: None,
+            event: None,
+        };
+        let score = self.compiled.as_ref().unwrap().eval(&ctx);
+
+        score
+    }
+}
+
+pub fn parse(config: &str) -> Result<Criterion, String> {
+    serde_json::from_str::<Script>(config)
+        .map(Criterion::Script)
+        .map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Var {
+    Slot,
+    Day,
+    Time,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Func {
+    Abs,
+    Max,
+    Min,
+    Pow,
+}
+
+#[derive(Debug)]
+enum Expr {
+    Num(f32),
+    Var(Var),
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    Bin(Op, Box<Expr>, Box<Expr>),
+    Call(Func, Vec<Expr>),
+    IsKind(EventKind),
+    Attends(Person),
+    InRoom(Room),
+    InRoomKind(RoomKind),
+    Sum(Box<Expr>, Box<Expr>),
+    Days(Box<Expr>),
+}
+
+struct Context<'a> {
+    solution: &'a Solution,
+    project: &'a Project,
+    day: Option<usize>,
+    event: Option<(usize, Event, Room)>,
+}
+
+/// Keeps NaN and infinities out of the scores
+fn finite(x: f32) -> f32 {
+    if x.is_finite() {
+        x
+    } else {
+        0.0
+    }
+}
+
+fn truth(b: bool) -> f32 {
+    if b {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+impl Expr {
+    fn eval(&self, ctx: &Context) -> f32 {
+        match self {
+            Expr::Num(n) => *n,
+            Expr::Var(v) => match v {
+                // Compile ensures the variables are bound
+                Var::Day => ctx
+                    .day
+                    .or(ctx
+                        .event
+                        .map(|(t, _, _)| ctx.project.config.slots_to_day(t)))
+                    .unwrap() as f32,
+                Var::Slot => ctx.project.config.offset_in_day(ctx.event.unwrap().0) as f32,
+                Var::Time => ctx.event.unwrap().0 as f32,
+            },
+            Expr::Neg(e) => -e.eval(ctx),
+            Expr::Not(e) => truth(e.eval(ctx) == 0.0),
+            Expr::Bin(op, a, b) => {
+                let a = a.eval(ctx);
+                // Short circuit
+                match op {
+                    Op::And if a == 0.0 => return 0.0,
+                    Op::Or if a != 0.0 => return 1.0,
+                    _ => {}
+                }
+                let b = b.eval(ctx);
+                match op {
+                    Op::Add => finite(a + b),
+                    Op::Sub => finite(a - b),
+                    Op::Mul => finite(a * b),
+                    Op::Div => finite(a / b),
+                    Op::Eq => truth(a == b),
+                    Op::Ne => truth(a != b),
+                    Op::Lt => truth(a < b),
+                    Op::Le => truth(a <= b),
+                    Op::Gt => truth(a > b),
+                    Op::Ge => truth(a >= b),
+                    Op::And | Op::Or => truth(b != 0.0),
+                }
+            }
+            Expr::Call(f, args) => {
+                let args: Vec<f32> = args.iter().map(|a| a.eval(ctx)).collect();
+                finite(match f {
+                    Func::Abs => args[0].abs(),
+                    Func::Max => args[0].max(args[1]),
+                    Func::Min => args[0].min(args[1]),
+                    Func::Pow => args[0].powf(args[1]),
+                })
+            }
+            Expr::IsKind(k) => {
+                let (_, e, _) = ctx.event.unwrap();
+                truth(ctx.project.events.kind(&e) == *k)
+            }
+            Expr::Attends(p) => {
+                let (_, e, _) = ctx.event.unwrap();
+                truth(ctx.project.people.events_attended_by(*p).contains(&e))
+            }
+            Expr::InRoom(r) => truth(ctx.event.unwrap().2 == *r),
+            Expr::InRoomKind(k) => {
+                let (_, _, r) = ctx.event.unwrap();
+                truth(ctx.project.rooms.room_kind(&r) == *k)
+            }
+            Expr::Sum(filter, body) => {
+                let days = match ctx.day {
+                    Some(d) => d..(d + 1),
+                    None => ctx.project.config.days(),
+                };
+
+                let mut sum = 0.0;
+                for d in days {
+                    for event in ctx.solution.events_of_day(d, ctx.project) {
+                        let inner = Context {
+                            event: Some(event),
+                            ..*ctx
+                        };
+                        if filter.eval(&inner) != 0.0 {
+                            sum += body.eval(&inner);
+                        }
+                    }
+                }
+                sum
+            }
+            Expr::Days(body) => ctx
+                .project
+                .config
+                .days()
+                .map(|d| {
+                    body.eval(&Context {
+                        day: Some(d),
+                        ..*ctx
+                    })
+                })
+                .sum(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f32),
+    Ident(String),
+    Str(String),
+    Op(&'static str),
+}
+
+fn tokenize(src: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let begin = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let s: String = chars[begin..i].iter().collect();
+            match s.parse() {
+                Ok(n) => tokens.push(Token::Num(n)),
+                Err(_) => return Err(format!("invalid number '{s}'")),
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            let begin = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[begin..i].iter().collect()));
+        } else if c == '"' {
+            let begin = i + 1;
+            i = begin;
+            while i < chars.len() && chars[i] != '"' {
+                i += 1;
+            }
+            if i == chars.len() {
+                return Err("unterminated string".to_owned());
+            }
+            tokens.push(Token::Str(chars[begin..i].iter().collect()));
+            i += 1;
+        } else {
+            let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+            let op = ["==", "!=", "<=", ">=", "&&", "||"]
+                .into_iter()
+                .find(|op| *op == two)
+                .or_else(|| {
+                    ["+", "-", "*", "/", "<", ">", "!", "(", ")", ","]
+                        .into_iter()
+                        .find(|op| op.starts_with(c))
+                });
+
+            match op {
+                Some(op) => {
+                    i += op.len();
+                    tokens.push(Token::Op(op));
+                }
+                None => return Err(format!("unexpected character '{c}'")),
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent parser, resolving names against the project on the fly
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    project: &'a Project,
+    /// Inside sum / count, so the current event is bound
+    in_aggregate: bool,
+    /// Inside days, so `day` is bound
+    in_days: bool,
+}
+
+fn compile(src: &str, project: &Project) -> Result<Expr, String> {
+    let mut p = Parser {
+        tokens: tokenize(src)?,
+        pos: 0,
+        project,
+        in_aggregate: false,
+        in_days: false,
+    };
+
+    let e = p.expr()?;
+    if let Some(t) = p.peek() {
+        return Err(format!("unexpected {:?}", t));
+    }
+
+    Ok(e)
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        t
+    }
+
+    fn eat_op(&mut self, op: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Op(o)) if *o == op) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_ident(&mut self, name: &str) -> bool {
+        if let Some(Token::Ident(i)) = self.peek() {
+            if i == name {
+                self.pos += 1;
+                return true;
+            }
+        }
+        false
+    }
+
+    fn expect_op(&mut self, op: &str) -> Result<(), String> {
+        if self.eat_op(op) {
+            Ok(())
+        } else {
+            Err(format!("expected '{op}', found {:?}", self.peek()))
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.and()?;
+        while self.eat_op("||") || self.eat_ident("or") {
+            lhs = Expr::Bin(Op::Or, Box::new(lhs), Box::new(self.and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.not()?;
+        while self.eat_op("&&") || self.eat_ident("and") {
+            lhs = Expr::Bin(Op::And, Box::new(lhs), Box::new(self.not()?));
+        }
+        Ok(lhs)
+    }
+
+    /// `not` negates a whole comparison, as in `not kind == "Math"`
+    fn not(&mut self) -> Result<Expr, String> {
+        if self.eat_op("!") || self.eat_ident("not") {
+            Ok(Expr::Not(Box::new(self.not()?)))
+        } else {
+            self.comparison()
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        // String matching on the current event
+        if let Some(Token::Ident(name)) = self.peek().cloned() {
+            if ["kind", "person", "room", "room_kind"].contains(&name.as_str()) {
+                return self.matching(&name);
+            }
+        }
+
+        let lhs = self.additive()?;
+        let ops = [
+            ("==", Op::Eq),
+            ("!=", Op::Ne),
+            ("<=", Op::Le),
+            (">=", Op::Ge),
+            ("<", Op::Lt),
+            (">", Op::Gt),
+        ];
+        for (s, op) in ops {
+            if self.eat_op(s) {
+                return Ok(Expr::Bin(op, Box::new(lhs), Box::new(self.additive()?)));
+            }
+        }
+        Ok(lhs)
+    }
+
+    fn matching(&mut self, name: &str) -> Result<Expr, String> {
+        if !self.in_aggregate {
+            return Err(format!("'{name}' used outside of sum / count"));
+        }
+        self.pos += 1;
+
+        let negate = if self.eat_op("==") {
+            false
+        } else if self.eat_op("!=") {
+            true
+        } else {
+            return Err(format!("'{name}' can only be compared with == or !="));
+        };
+
+        let value = match self.next() {
+            Some(Token::Str(s)) => s,
+            t => return Err(format!("expected a string after '{name}', found {:?}", t)),
+        };
+
+        let project = self.project;
+        let e = match name {
+            "kind" => project.events.find_kind(&value).map(Expr::IsKind),
+            "person" => project.people.find_person(&value).map(Expr::Attends),
+            "room" => project.rooms.find_room(&value).map(Expr::InRoom),
+            "room_kind" => project.rooms.find_kind(&value).map(Expr::InRoomKind),
+            _ => unreachable!(),
+        };
+        let e = e.ok_or(format!("unknown {name} \"{value}\""))?;
+
+        Ok(if negate { Expr::Not(Box::new(e)) } else { e })
+    }
+
+    fn additive(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.term()?;
+        loop {
+            let op = if self.eat_op("+") {
+                Op::Add
+            } else if self.eat_op("-") {
+                Op::Sub
+            } else {
+                return Ok(lhs);
+            };
+            lhs = Expr::Bin(op, Box::new(lhs), Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+        loop {
+            let op = if self.eat_op("*") {
+                Op::Mul
+            } else if self.eat_op("/") {
+                Op::Div
+            } else {
+                return Ok(lhs);
+            };
+            lhs = Expr::Bin(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat_op("-") {
+            Ok(Expr::Neg(Box::new(self.unary()?)))
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Num(n)) => Ok(Expr::Num(n)),
+            Some(Token::Op("(")) => {
+                let e = self.expr()?;
+                self.expect_op(")")?;
+                Ok(e)
+            }
+            Some(Token::Ident(name)) => {
+                if self.eat_op("(") {
+                    let e = self.call(&name)?;
+                    self.expect_op(")")?;
+                    Ok(e)
+                } else {
+                    self.variable(&name)
+                }
+            }
+            t => Err(format!("unexpected {:?}", t)),
+        }
+    }
+
+    fn variable(&mut self, name: &str) -> Result<Expr, String> {
+        let v = match name {
+            "slot" => Var::Slot,
+            "time" => Var::Time,
+            "day" => Var::Day,
+            _ => return Err(format!("unknown variable '{name}'")),
+        };
+
+        let bound = self.in_aggregate || (v == Var::Day && self.in_days);
+        if !bound {
+            return Err(format!("'{name}' used outside of sum / count"));
+        }
+
+        Ok(Expr::Var(v))
+    }
+
+    fn args(&mut self, n: usize) -> Result<Vec<Expr>, String> {
+        let mut args = vec![self.expr()?];
+        for _ in 1..n {
+            self.expect_op(",")?;
+            args.push(self.expr()?);
+        }
+        Ok(args)
+    }
+
+    fn call(&mut self, name: &str) -> Result<Expr, String> {
+        match name {
+            "sum" | "count" => {
+                if self.in_aggregate {
+                    return Err(format!("nested '{name}'"));
+                }
+
+                self.in_aggregate = true;
+                let filter = self.expr()?;
+                let body = if name == "sum" {
+                    self.expect_op(",")?;
+                    self.expr()?
+                } else {
+                    Expr::Num(1.0)
+                };
+                self.in_aggregate = false;
+
+                Ok(Expr::Sum(Box::new(filter), Box::new(body)))
+            }
+            "days" => {
+                if self.in_aggregate || self.in_days {
+                    return Err("'days' must not be nested".to_owned());
+                }
+
+                self.in_days = true;
+                let body = self.expr()?;
+                self.in_days = false;
+
+                Ok(Expr::Days(Box::new(body)))
+            }
+            "abs" => Ok(Expr::Call(Func::Abs, self.args(1)?)),
+            "max" => Ok(Expr::Call(Func::Max, self.args(2)?)),
+            "min" => Ok(Expr::Call(Func::Min, self.args(2)?)),
+            "pow" => Ok(Expr::Call(Func::Pow, self.args(2)?)),
+            _ => Err(format!("unknown function '{name}'")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize("sum(kind == \"Math\" and slot >= 6, slot - 6.5)").unwrap();
+
+        assert_eq!(tokens[0], Token::Ident("sum".to_owned()));
+        assert_eq!(tokens[3], Token::Op("=="));
+        assert_eq!(tokens[4], Token::Str("Math".to_owned()));
+        assert_eq!(tokens[7], Token::Op(">="));
+        assert_eq!(tokens[12], Token::Num(6.5));
+        assert!(tokenize("\"open").is_err());
+        assert!(tokenize("a # b").is_err());
+    }
+
+    #[test]
+    fn test_compile_and_evaluate() {
+        let project = Project::parse("./converted/comp01");
+        let empty = Solution::empty(&project);
+        let ctx = Context {
+            solution: &empty,
+            project: &project,
+            day: None,
+            event: None,
+        };
+
+        let eval = |src: &str| compile(src, &project).unwrap().eval(&ctx);
+
+        assert_eq!(eval("1 + 2 * 3"), 7.0);
+        assert_eq!(eval("-(1 + 2) * 3 / 9"), -1.0);
+        assert_eq!(eval("max(2, 3) + abs(-1) + pow(2, 3)"), 12.0);
+        assert_eq!(eval("1 < 2 and not 2 < 1"), 1.0);
+        assert_eq!(eval("not 1 == 2 and !(1 > 2)"), 1.0);
+        assert_eq!(eval("1 / 0 + pow(-1, 0.5)"), 0.0);
+        assert_eq!(eval("days(day)"), 10.0);
+        assert_eq!(eval("count(kind == \"c0001\" and slot > 2)"), 0.0);
+
+        assert!(compile("slot + 1", &project).is_err());
+        assert!(compile("count(kind == \"nope\")", &project).is_err());
+        assert!(compile("sum(count(slot > 1), 1)", &project).is_err());
+        assert!(compile("1 +", &project).is_err());
+        assert!(compile("(1", &project).is_err());
+    }
+
+    #[test]
+    fn test_parse_penalty() {
+        let project = Project::parse("./converted/comp01");
+        let e = Event(0);
+        let name = project.events.kind_name(&e);
+
+        let mut s = Solution::empty(&project);
+        s.insert(5, e, Room(0), &project);
+
+        let entry = format!(
+            r#"{{"expr": "sum(kind == \"{name}\" and slot > 3, slot - 3)", "direction": "minimize"}}"#
+        );
+        let mut c = parse(&entry).unwrap();
+        c.init(&project);
+
+        let value = c.evaluate(&s, &project);
+        assert_eq!(value, 2.0);
+        // A late event lowers the score
+        assert_eq!(c.objective().score(value), -2.0);
+    }
+
+    #[test]
+    fn test_evaluate_placed_events() {
+        let project = Project::parse("./converted/comp01");
+        let kind = project.events.kind(&Event(0));
+        let name = project.events.kind_name(&Event(0)).to_owned();
+        let mut events = project.events.events_with_kind(kind).into_iter();
+
+        // Slot 0 of day 0 and slot 2 of day 1
+        let mut s = Solution::empty(&project);
+        s.insert(0, events.next().unwrap(), Room(0), &project);
+        s.insert(8, events.next().unwrap(), Room(0), &project);
+        let ctx = Context {
+            solution: &s,
+            project: &project,
+            day: None,
+            event: None,
+        };
+
+        let eval = |src: &str| {
+            let src = src.replace("K", &name);
+            compile(&src, &project).unwrap().eval(&ctx)
+        };
+
+        assert_eq!(eval("count(kind == \"K\")"), 2.0);
+        assert_eq!(eval("sum(kind == \"K\", slot + day * 10)"), 12.0);
+        assert_eq!(eval("count(not kind == \"K\")"), 0.0);
+        assert_eq!(eval("count(not slot > 1)"), 1.0);
+        assert_eq!(eval("count(not slot > 1 and day == 0)"), 1.0);
+        assert_eq!(eval("days(count(day == 1) * 2)"), 2.0);
+        // Division by zero at slot 0
+        assert_eq!(eval("sum(kind == \"K\", slot / slot)"), 1.0);
+        assert_eq!(eval("sum(time > 0, time / 4)"), 2.0);
+        assert_eq!(eval("sum(kind == \"K\", pow(-1, 0.5))"), 0.0);
+    }
+}
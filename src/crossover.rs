@@ -0,0 +1,152 @@
+// Recombination of two timetables.
+//
+// Events of a kind are interchangeable, so a child is built from placements
+// (slot, kind, room) of its parents and events are dealt to them afterwards.
+// Placements breaking a hard constraint are dropped, and the events left over
+// are put back by the tabu search of the initial phase.
+
+use std::collections::HashMap;
+
+use rand::{seq::SliceRandom, thread_rng, Rng};
+
+use crate::{
+    fatal,
+    initial::{self, InitialSolution},
+    optimize::TIMEMAP,
+    project::{Event, EventKind, Project, Room},
+};
+
+/// Child with the days of `a` where `from_a(day)`, and the other days of `b`
+pub fn day_crossover<F: Fn(usize) -> bool>(
+    a: &TIMEMAP,
+    b: &TIMEMAP,
+    from_a: F,
+    project: &Project,
+) -> InitialSolution {
+    let pick = |s: &TIMEMAP, keep: bool| -> Vec<(usize, Event, Room)> {
+        placements(s)
+            .into_iter()
+            .filter(|(t, _, _)| from_a(project.config.slots_to_day(*t)) == keep)
+            .collect()
+    };
+
+    assemble(&[pick(a, true), pick(b, false)], project)
+}
+
+/// Child with the events of `a` whose kind is in `from_a`, and the others of `b`
+pub fn kind_crossover<F: Fn(EventKind) -> bool>(
+    a: &TIMEMAP,
+    b: &TIMEMAP,
+    from_a: F,
+    project: &Project,
+) -> InitialSolution {
+    let pick = |s: &TIMEMAP, keep: bool| -> Vec<(usize, Event, Room)> {
+        placements(s)
+            .into_iter()
+            .filter(|(_, e, _)| from_a(project.events.kind(e)) == keep)
+            .collect()
+    };
+
+    // The events of `b` are placed first, those of `a` fill the gaps
+    assemble(&[pick(b, false), pick(a, true)], project)
+}
+
+/// Child of `a` and `b` by `method` ("day" or "kind") on random halves, repaired.
+/// None if it could not be made feasible.
+pub fn crossover(a: &TIMEMAP, b: &TIMEMAP, method: &str, project: &Project) -> Option<TIMEMAP> {
+    let mut rng = thread_rng();
+
+    let child = match method {
+        "day" => {
+            let days: Vec<bool> = project.config.days().map(|_| rng.gen()).collect();
+            day_crossover(a, b, |d| days[d], project)
+        }
+        "kind" => {
+            let mut kinds: HashMap<EventKind, bool> = HashMap::new();
+            for e in project.events.iter_all() {
+                kinds
+                    .entry(project.events.kind(&e))
+                    .or_insert_with(|| rng.gen());
+            }
+            kind_crossover(a, b, |k| kinds[&k], project)
+        }
+        _ => fatal!("Invalid crossover: {}", method),
+    };
+
+    let child = initial::repair(project, child);
+    child.is_complete().then(|| child.into_inner())
+}
+
+fn placements(s: &TIMEMAP) -> Vec<(usize, Event, Room)> {
+    s.iter()
+        .enumerate()
+        .flat_map(|(t, v)| v.iter().map(move |(e, r)| (t, *e, *r)))
+        .collect()
+}
+
+/// Build a partial solution from the placements of each part in order.
+/// A placement is kept if it clashes with no kept one and an event of its kind remains.
+fn assemble(parts: &[Vec<(usize, Event, Room)>], project: &Project) -> InitialSolution {
+    let mut pools: HashMap<EventKind, Vec<Event>> = HashMap::new();
+    for e in project.events.iter_all() {
+        pools.entry(project.events.kind(&e)).or_default().push(e);
+    }
+    for pool in pools.values_mut() {
+        pool.shuffle(&mut thread_rng());
+    }
+
+    let mut events: TIMEMAP = project.config.iter_slots().map(|_| vec![]).collect();
+    let mut per_day: HashMap<(usize, EventKind), usize> = HashMap::new();
+
+    for (t, e, r) in parts.iter().flatten() {
+        let (t, r) = (*t, *r);
+        let kind = project.events.kind(e);
+        let day = project.config.slots_to_day(t);
+
+        let clash = events[t]
+            .iter()
+            .any(|(e2, r2)| *r2 == r || project.events.have_people_conflict(*e, *e2));
+        let count = per_day.get(&(day, kind)).copied().unwrap_or(0);
+        if clash || count >= project.events.max_per_day(e) {
+            continue;
+        }
+
+        let Some(e) = pools.get_mut(&kind).and_then(|p| p.pop()) else {
+            continue;
+        };
+
+        events[t].push((e, r));
+        per_day.insert((day, kind), count + 1);
+    }
+
+    let unassigned = pools.into_values().flatten().collect();
+    InitialSolution::from_parts(events, unassigned)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::violations::find_violations;
+
+    #[test]
+    fn test_crossover() {
+        let project = Project::parse("./converted/comp01");
+        let a = initial::find_initial_solution(&project, false).unwrap();
+        let b = initial::find_initial_solution(&project, false).unwrap();
+
+        // Taking everything from one parent gives it back, up to event labels
+        let same = day_crossover(&a, &b, |_| true, &project);
+        assert!(same.is_complete());
+        for (t, slot) in same.events().iter().enumerate() {
+            assert_eq!(slot.len(), a[t].len());
+        }
+
+        // The repair may fail, but never gives an infeasible child
+        for method in ["day", "kind"] {
+            if let Some(child) = crossover(&a, &b, method, &project) {
+                let violations = find_violations(&child, &project);
+                assert!(violations.is_empty(), "{violations}");
+            }
+        }
+    }
+}
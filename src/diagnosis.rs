@@ -0,0 +1,238 @@
+// Explain why no feasible initial solution could be found
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use crate::project::{Event, Project};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Issue {
+    /// A person attends more events than there are slots
+    PersonOverloaded {
+        person: String,
+        events: usize,
+        slots: usize,
+    },
+    /// More events need a room kind than there are (room, slot) pairs
+    RoomKindOverloaded {
+        kind: String,
+        events: usize,
+        capacity: usize,
+    },
+    /// `num_per_week > max_per_day × days`
+    MaxPerDayTooSmall {
+        event: String,
+        num_per_week: usize,
+        max_per_day: usize,
+        days: usize,
+    },
+    /// Pairwise conflicting events which can not all get a different slot
+    Clique { events: Vec<String>, slots: usize },
+    /// Events left unassigned by the best partial solution
+    Unassigned { event: String, count: usize },
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Issue::PersonOverloaded {
+                person,
+                events,
+                slots,
+            } => write!(f, "{person} attends {events} events, but there are only {slots} slots"),
+            Issue::RoomKindOverloaded {
+                kind,
+                events,
+                capacity,
+            } => write!(
+                f,
+                "{events} events need a {kind} room, but only {capacity} (room, slot) pairs exist"
+            ),
+            Issue::MaxPerDayTooSmall {
+                event,
+                num_per_week,
+                max_per_day,
+                days,
+            } => write!(
+                f,
+                "{event} happens {num_per_week} times per week, but at most {max_per_day} per day on {days} days"
+            ),
+            Issue::Clique { events, slots } => write!(
+                f,
+                "{} events conflict with each other, but there are only {slots} slots: {}",
+                events.len(),
+                events.join(", ")
+            ),
+            Issue::Unassigned { event, count } => write!(f, "{count} × {event} unassigned"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diagnosis(pub Vec<Issue>);
+
+impl Diagnosis {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Issue> {
+        self.0.iter()
+    }
+}
+
+impl Display for Diagnosis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No obvious cause of infeasibility found");
+        }
+
+        for i in self.iter() {
+            writeln!(f, "  {i}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Check the project for simple reasons of infeasibility.
+/// `unassigned` are the events left over by the best partial solution.
+pub fn diagnose(project: &Project, unassigned: &[Event]) -> Diagnosis {
+    let mut issues = vec![];
+    let slots = project.config.iter_slots().len();
+
+    for p in project.people.iter_all() {
+        let events = project.people.events_attended_by(p).len();
+        if events > slots {
+            issues.push(Issue::PersonOverloaded {
+                person: project.people.person_name(p).to_owned(),
+                events,
+                slots,
+            });
+        }
+    }
+
+    for kind in project.rooms.iter_kinds() {
+        let events = project.events.events_with_room_kind(*kind).len();
+        let capacity = project.rooms.rooms_with_kind(kind).len() * slots;
+        if events > capacity {
+            issues.push(Issue::RoomKindOverloaded {
+                kind: project.rooms.kind_name(kind).to_owned(),
+                events,
+                capacity,
+            });
+        }
+    }
+
+    let days = project.config.days().len();
+    let mut seen = HashSet::new();
+    for e in project.events.iter_all() {
+        let kind = project.events.kind(&e);
+        if !seen.insert(kind) {
+            continue;
+        }
+
+        let num_per_week = project.events.events_with_kind(kind).len();
+        let max_per_day = project.events.max_per_day(&e);
+        if num_per_week > max_per_day * days {
+            issues.push(Issue::MaxPerDayTooSmall {
+                event: project.events.kind_name(&e).to_owned(),
+                num_per_week,
+                max_per_day,
+                days,
+            });
+        }
+    }
+
+    let clique = greedy_clique(project);
+    if clique.len() > slots {
+        issues.push(Issue::Clique {
+            events: clique
+                .iter()
+                .map(|e| project.events.kind_name(e).to_owned())
+                .collect(),
+            slots,
+        });
+    }
+
+    let mut counter: HashMap<&str, usize> = HashMap::new();
+    for e in unassigned {
+        *counter.entry(project.events.kind_name(e)).or_default() += 1;
+    }
+    let mut counter: Vec<_> = counter.into_iter().collect();
+    counter.sort();
+    for (event, count) in counter {
+        issues.push(Issue::Unassigned {
+            event: event.to_owned(),
+            count,
+        });
+    }
+
+    Diagnosis(issues)
+}
+
+/// Find a large clique in the people conflict graph, greedily from every event
+fn greedy_clique(project: &Project) -> Vec<Event> {
+    let events: Vec<Event> = project.events.iter_all().collect();
+    let neighbors: Vec<Vec<Event>> = events
+        .iter()
+        .map(|e1| {
+            events
+                .iter()
+                .filter(|e2| e1 != *e2 && project.events.have_people_conflict(*e1, **e2))
+                .copied()
+                .collect()
+        })
+        .collect();
+
+    let mut best = vec![];
+
+    for e in events.iter() {
+        let mut candidates = neighbors[e.0].clone();
+        candidates.sort_by_key(|c| std::cmp::Reverse(neighbors[c.0].len()));
+
+        let mut clique = vec![*e];
+        for c in candidates {
+            if clique
+                .iter()
+                .all(|e| project.events.have_people_conflict(*e, c))
+            {
+                clique.push(c);
+            }
+        }
+
+        if clique.len() > best.len() {
+            best = clique;
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_diagnose_tiny_week() {
+        // comp01 squeezed into a single slot
+        let mut project = Project::parse("./converted/comp01");
+        project.config = crate::project::Config::new(1, 1);
+        let unassigned: Vec<Event> = project.events.iter_all().take(3).collect();
+        let diagnosis = diagnose(&project, &unassigned);
+
+        let has = |f: fn(&Issue) -> bool| diagnosis.iter().any(f);
+        assert!(has(|i| matches!(
+            i,
+            Issue::PersonOverloaded { slots: 1, .. }
+        )));
+        assert!(has(|i| matches!(i, Issue::RoomKindOverloaded { .. })));
+        assert!(has(|i| matches!(
+            i,
+            Issue::MaxPerDayTooSmall { days: 1, .. }
+        )));
+        assert!(has(|i| matches!(i, Issue::Clique { slots: 1, .. })));
+        assert!(has(|i| matches!(i, Issue::Unassigned { .. })));
+    }
+}
@@ -0,0 +1,188 @@
+// Exact MILP model of a project, for small instances
+//
+// Events of the same kind are interchangeable, so the model only decides how many
+// events of each kind take place at each (slot, room):
+//
+//   y[k, t, r] ∈ {0, 1}, for rooms r of the room kind of k
+//
+//   Σ_{t, r} y[k, t, r] = num_per_week(k)                       every event is scheduled
+//   Σ_k y[k, t, r] ≤ 1                                          room conflicts
+//   Σ_{k attended by p} Σ_r y[k, t, r] ≤ 1                      people conflicts
+//   Σ_{t in day, r} y[k, t, r] ≤ max_per_day(k)                 max per day
+//   y[kind(e), t, r] = 1                                        fixed assignments
+//
+// The objective is the sum of all criteria which are linear in the assignment
+// (see `CriterionT::linear`); the others can not be modelled and are ignored.
+
+use std::collections::{HashMap, HashSet};
+
+use microlp::{ComparisonOp, OptimizationDirection, Problem, Variable};
+
+use crate::{
+    criteria::CriterionT,
+    optimize::TIMEMAP,
+    project::{Event, EventKind, Project, Room},
+    warn,
+};
+
+#[derive(Debug, Clone)]
+pub struct ExactSolution {
+    pub timemap: TIMEMAP,
+    /// Sum of the linear criteria, proven optimal
+    pub objective: f64,
+}
+
+/// Solve the project to optimality. `fixed` events are forced to their (slot, room).
+pub fn solve(project: &Project, fixed: &[(Event, usize, Room)]) -> Result<ExactSolution, String> {
+    let mut kinds: HashMap<EventKind, Vec<Event>> = HashMap::new();
+    for e in project.events.iter_all() {
+        kinds.entry(project.events.kind(&e)).or_default().push(e);
+    }
+    let mut kinds: Vec<(EventKind, Vec<Event>)> = kinds.into_iter().collect();
+    kinds.sort_by_key(|(k, _)| k.0);
+
+    let linear = project
+        .criteria()
+        .iter()
+        .filter(|c| {
+            let is_linear = c.linear(Event(0), 0, project).is_some();
+            if !is_linear {
+                warn!("A criterion is not linear and ignored by the exact solver");
+            }
+            is_linear
+        })
+        .collect::<Vec<_>>();
+
+    let mut problem = Problem::new(OptimizationDirection::Maximize);
+    let mut vars: HashMap<(EventKind, usize, Room), Variable> = HashMap::new();
+
+    for (k, events) in kinds.iter() {
+        let rooms = project
+            .rooms
+            .rooms_with_kind(&project.events.room_kind(&events[0]));
+
+        for t in project.config.iter_slots() {
+            let score: f32 = linear
+                .iter()
+                .map(|c| {
+                    let value = c.linear(events[0], t, project).unwrap();
+                    c.objective().score(value)
+                })
+                .sum();
+
+            for r in rooms.iter() {
+                vars.insert((*k, t, *r), problem.add_binary_var(score as f64));
+            }
+        }
+    }
+
+    let vars_of = |k: EventKind, slots: &mut dyn Iterator<Item = usize>| -> Vec<(Variable, f64)> {
+        let mut v = vec![];
+        for t in slots {
+            for r in project.rooms.iter_all() {
+                if let Some(var) = vars.get(&(k, t, r)) {
+                    v.push((*var, 1.0));
+                }
+            }
+        }
+        v
+    };
+
+    for (k, events) in kinds.iter() {
+        let all = vars_of(*k, &mut project.config.iter_slots());
+        problem.add_constraint(&all, ComparisonOp::Eq, events.len() as f64);
+
+        let max_per_day = project.events.max_per_day(&events[0]) as f64;
+        for day in project.config.days() {
+            let of_day = vars_of(*k, &mut project.config.slots_of_day(day));
+            problem.add_constraint(&of_day, ComparisonOp::Le, max_per_day);
+        }
+    }
+
+    for t in project.config.iter_slots() {
+        for r in project.rooms.iter_all() {
+            let in_room: Vec<(Variable, f64)> = kinds
+                .iter()
+                .filter_map(|(k, _)| vars.get(&(*k, t, r)).map(|v| (*v, 1.0)))
+                .collect();
+            if in_room.len() > 1 {
+                problem.add_constraint(&in_room, ComparisonOp::Le, 1.0);
+            }
+        }
+    }
+
+    // People attending the same kinds share constraints
+    let mut attended_kinds: HashSet<Vec<EventKind>> = HashSet::new();
+    for p in project.people.iter_all() {
+        let mut k: Vec<EventKind> = project
+            .people
+            .events_attended_by(p)
+            .iter()
+            .map(|e| project.events.kind(e))
+            .collect();
+        k.sort_by_key(|k| k.0);
+        k.dedup();
+        attended_kinds.insert(k);
+    }
+
+    for k in attended_kinds {
+        for t in project.config.iter_slots() {
+            let busy: Vec<(Variable, f64)> = k
+                .iter()
+                .flat_map(|k| vars_of(*k, &mut std::iter::once(t)))
+                .collect();
+            if busy.len() > 1 {
+                problem.add_constraint(&busy, ComparisonOp::Le, 1.0);
+            }
+        }
+    }
+
+    for (e, t, r) in fixed {
+        match vars.get(&(project.events.kind(e), *t, *r)) {
+            Some(v) => problem.add_constraint([(*v, 1.0)], ComparisonOp::Eq, 1.0),
+            None => return Err(format!("{:?} can not use {:?}", e, r)),
+        }
+    }
+
+    let solution = problem.solve().map_err(|e| e.to_string())?;
+
+    let mut timemap: TIMEMAP = project.config.iter_slots().map(|_| vec![]).collect();
+    for (k, events) in kinds.iter() {
+        let mut events = events.iter();
+
+        for t in project.config.iter_slots() {
+            for r in project.rooms.iter_all() {
+                if let Some(v) = vars.get(&(*k, t, r)) {
+                    if solution[*v] > 0.5 {
+                        timemap[t].push((*events.next().unwrap(), r));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(ExactSolution {
+        timemap,
+        objective: solution.objective(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::violations::find_violations;
+
+    #[test]
+    fn test_solve_tiny_instance() {
+        // A single day of comp01 with only a few events
+        let project = crate::project::test_util::tiny_project(
+            r#"{"event_time_distance": [{"kind": "max", "event": "a", "time": 0}]}"#,
+        );
+        let s = solve(&project, &[]).unwrap();
+
+        let violations = find_violations(&s.timemap, &project);
+        assert!(violations.is_empty(), "{violations}");
+        // One `a` per day as early as possible: 0 + 6² + 12²
+        assert_eq!(s.objective, -180.0);
+    }
+}
@@ -0,0 +1,152 @@
+// Solutions with names instead of indices, stable across runs
+//
+// Event indices are shuffled every time a project is parsed, so a `TIMEMAP`
+// is only meaningful within one process. Events of the same kind are
+// interchangeable, so an exported solution refers to them by kind name.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    initial::InitialSolution,
+    optimize::TIMEMAP,
+    project::{Event, Project},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedEvent {
+    pub slot: usize,
+    pub event: String,
+    pub room: String,
+}
+
+pub type ExportedSolution = Vec<ExportedEvent>;
+
+/// A partial solution with the names of the events left over
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedPartial {
+    pub solution: ExportedSolution,
+    pub unassigned: Vec<String>,
+}
+
+/// Either a single solution or several of them
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ExportedFile {
+    One(ExportedSolution),
+    Many(Vec<ExportedSolution>),
+}
+
+pub fn export(s: &TIMEMAP, project: &Project) -> ExportedSolution {
+    let mut exported = vec![];
+
+    for (t, events) in s.iter().enumerate() {
+        for (e, r) in events {
+            exported.push(ExportedEvent {
+                slot: t,
+                event: project.events.kind_name(e).to_owned(),
+                room: project.rooms.room_name(r).to_owned(),
+            })
+        }
+    }
+
+    exported
+}
+
+/// Map an exported solution back to events of `project`.
+/// Extra events of a kind are mapped to already used events, so they show up as duplicates.
+pub fn import(s: &ExportedSolution, project: &Project) -> Result<TIMEMAP, String> {
+    let mut timemap: TIMEMAP = project.config.iter_slots().map(|_| vec![]).collect();
+    let mut used = vec![0; project.events.len()];
+
+    for e in s {
+        let kind = project
+            .events
+            .find_kind(&e.event)
+            .ok_or(format!("Unknown event: {}", e.event))?;
+        let room = project
+            .rooms
+            .find_room(&e.room)
+            .ok_or(format!("Unknown room: {}", e.room))?;
+
+        if e.slot >= timemap.len() {
+            return Err(format!("Slot out of range: {}", e.slot));
+        }
+
+        // Prefer the least used event of this kind
+        let event: Event = project
+            .events
+            .events_with_kind(kind)
+            .into_iter()
+            .min_by_key(|e| (used[e.0], e.0))
+            .unwrap();
+        used[event.0] += 1;
+
+        timemap[e.slot].push((event, room));
+    }
+
+    Ok(timemap)
+}
+
+/// Read a file of one or many exported solutions
+pub fn read<P: AsRef<Path>>(path: P, project: &Project) -> Result<Vec<TIMEMAP>, String> {
+    let file = std::fs::File::open(path.as_ref()).map_err(|e| e.to_string())?;
+
+    let solutions = match serde_json::from_reader(file).map_err(|e| e.to_string())? {
+        ExportedFile::One(s) => vec![s],
+        ExportedFile::Many(s) => s,
+    };
+
+    solutions.iter().map(|s| import(s, project)).collect()
+}
+
+pub fn write<P: AsRef<Path>>(path: P, solutions: &[TIMEMAP], project: &Project) {
+    let exported: Vec<ExportedSolution> = solutions.iter().map(|s| export(s, project)).collect();
+
+    let file = std::fs::File::create(path.as_ref()).unwrap();
+    serde_json::to_writer(file, &exported).unwrap();
+}
+
+pub fn write_partial<P: AsRef<Path>>(path: P, s: &InitialSolution, project: &Project) {
+    let exported = ExportedPartial {
+        solution: export(s.events(), project),
+        unassigned: s
+            .unassigned_events()
+            .iter()
+            .map(|e| project.events.kind_name(e).to_owned())
+            .collect(),
+    };
+
+    let file = std::fs::File::create(path.as_ref()).unwrap();
+    serde_json::to_writer(file, &exported).unwrap();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::project::Room;
+
+    #[test]
+    fn test_export_import() {
+        let project = Project::parse("./converted/comp01");
+        let mut events: TIMEMAP = project.config.iter_slots().map(|_| vec![]).collect();
+        events[3].push((Event(0), Room(1)));
+        events[4].push((Event(1), Room(0)));
+
+        let exported = export(&events, &project);
+        assert_eq!(exported.len(), 2);
+        assert_eq!(exported[0].event, project.events.kind_name(&Event(0)));
+
+        let imported = import(&exported, &project).unwrap();
+        assert_eq!(imported[3][0].1, Room(1));
+        assert_eq!(
+            project.events.kind(&imported[4][0].0),
+            project.events.kind(&Event(1))
+        );
+
+        let mut unknown = exported.clone();
+        unknown[0].room = "nowhere".to_owned();
+        assert!(import(&unknown, &project).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+// Memory of the solutions already visited.
+//
+// A solution is identified by its Zobrist hash: the xor of a random key for each of
+// its placements (event, slot, room). Moving an event xors its old placement out and
+// the new one in, so `Solution` keeps its hash up to date at the cost of two xors.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::{
+    optimize::TIMEMAP,
+    project::{Event, Room},
+};
+
+/// Random key of the placement of `e` in slot `t` and room `r`
+#[inline]
+pub fn zobrist(e: Event, t: usize, r: Room) -> u64 {
+    // splitmix64 of the packed placement: keys are fixed without storing a table
+    let mut z = (e.0 as u64)
+        .wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        .wrapping_add((t as u64) << 40)
+        .wrapping_add((r.0 as u64) << 20)
+        .wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Hash of a whole timetable, the xor of the keys of its placements
+pub fn fingerprint(events: &TIMEMAP) -> u64 {
+    events
+        .iter()
+        .enumerate()
+        .flat_map(|(t, v)| v.iter().map(move |(e, r)| zobrist(*e, t, *r)))
+        .fold(0, |h, k| h ^ k)
+}
+
+/// Hashes of the last `capacity` distinct solutions visited, the least recently
+/// visited being forgotten first
+pub struct History {
+    capacity: usize,
+    /// Time of the last visit of each hash
+    visits: HashMap<u64, usize>,
+    /// Visits in order, some outdated by a later visit of the same hash
+    order: VecDeque<(u64, usize)>,
+    time: usize,
+}
+
+impl History {
+    pub fn with_capacity(capacity: usize) -> Self {
+        History {
+            capacity: capacity.max(1),
+            visits: HashMap::new(),
+            order: VecDeque::new(),
+            time: 0,
+        }
+    }
+
+    pub fn contains(&self, hash: u64) -> bool {
+        self.visits.contains_key(&hash)
+    }
+
+    /// Record a visit, true if the solution was not remembered
+    pub fn visit(&mut self, hash: u64) -> bool {
+        self.time += 1;
+        let novel = self.visits.insert(hash, self.time).is_none();
+        self.order.push_back((hash, self.time));
+
+        while self.visits.len() > self.capacity {
+            let (oldest, time) = self.order.pop_front().unwrap();
+            if self.visits.get(&oldest) == Some(&time) {
+                self.visits.remove(&oldest);
+            }
+        }
+
+        // Drop outdated visits once they outnumber the remembered ones
+        if self.order.len() > 2 * self.capacity {
+            let visits = &self.visits;
+            self.order.retain(|(h, t)| visits.get(h) == Some(t));
+        }
+
+        novel
+    }
+
+    pub fn len(&self) -> usize {
+        self.visits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.visits.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint() {
+        let a = vec![vec![(Event(0), Room(0)), (Event(1), Room(1))], vec![]];
+        let b = vec![vec![(Event(1), Room(1)), (Event(0), Room(0))], vec![]];
+        let c = vec![vec![(Event(1), Room(1))], vec![(Event(0), Room(0))]];
+
+        // Independent of the order in a slot
+        assert_eq!(fingerprint(&a), fingerprint(&b));
+        assert_ne!(fingerprint(&a), fingerprint(&c));
+
+        // Moving event 0 updates the hash
+        let moved = fingerprint(&a) ^ zobrist(Event(0), 0, Room(0)) ^ zobrist(Event(0), 1, Room(0));
+        assert_eq!(moved, fingerprint(&c));
+    }
+
+    #[test]
+    fn test_lru() {
+        let mut history = History::with_capacity(2);
+        assert!(history.visit(1));
+        assert!(history.visit(2));
+        assert!(!history.visit(1));
+
+        // 2 is the least recently visited
+        assert!(history.visit(3));
+        assert!(history.contains(1) && history.contains(3));
+        assert!(!history.contains(2));
+        assert_eq!(history.len(), 2);
+
+        for h in 0..100 {
+            history.visit(h % 3);
+        }
+        assert!(history.order.len() <= 4);
+    }
+}
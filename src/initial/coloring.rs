@@ -0,0 +1,167 @@
+// Constructive heuristics colouring the event conflict graph with slots
+
+use rand::{seq::SliceRandom, thread_rng};
+
+use crate::{
+    assignment,
+    optimize::Solution,
+    project::{Event, Project, Room},
+};
+
+use super::InitialSolution;
+
+/// Events sharing attendees, by event index
+fn conflict_graph(project: &Project) -> Vec<Vec<Event>> {
+    project
+        .events
+        .iter_all()
+        .map(|e1| {
+            project
+                .events
+                .iter_all()
+                .filter(|e2| e1 != *e2 && project.events.have_people_conflict(e1, *e2))
+                .collect()
+        })
+        .collect()
+}
+
+struct Coloring<'a> {
+    project: &'a Project,
+    graph: Vec<Vec<Event>>,
+    x: InitialSolution,
+    slot_of: Vec<Option<usize>>,
+}
+
+impl<'a> Coloring<'a> {
+    fn new(project: &'a Project) -> Self {
+        Coloring {
+            project,
+            graph: conflict_graph(project),
+            x: InitialSolution::new(vec![], project.config.iter_slots()),
+            slot_of: vec![None; project.events.len()],
+        }
+    }
+
+    fn degree(&self, e: Event) -> usize {
+        self.graph[e.0].len()
+    }
+
+    /// Number of distinct slots used by neighbors
+    fn saturation(&self, e: Event) -> usize {
+        let mut slots: Vec<usize> = self.graph[e.0]
+            .iter()
+            .filter_map(|n| self.slot_of[n.0])
+            .collect();
+        slots.sort();
+        slots.dedup();
+        slots.len()
+    }
+
+    /// Rooms for slot `t` with `e` added, if `e` fits there without violating any hard constraint
+    fn fits(&self, e: Event, t: usize) -> Option<Vec<(Event, Room)>> {
+        let project = self.project;
+
+        if self.graph[e.0].iter().any(|n| self.slot_of[n.0] == Some(t)) {
+            return None;
+        }
+
+        let kind = project.events.kind(&e);
+        let same_kind = project
+            .config
+            .slots_of_same_day(t)
+            .flat_map(|tt| self.x.events[tt].iter())
+            .filter(|(e2, _)| project.events.kind(e2) == kind)
+            .count();
+        if same_kind >= project.events.max_per_day(&e) {
+            return None;
+        }
+
+        let mut events: Vec<Event> = self.x.events[t].iter().map(|(e, _)| *e).collect();
+        events.push(e);
+        assignment::match_rooms(&events, project, |_, _| 0)
+    }
+
+    /// Put `e` in the least occupied slot it fits in, or eject it
+    fn assign(&mut self, e: Event) {
+        let mut slots: Vec<usize> = self.project.config.iter_slots().collect();
+        slots.shuffle(&mut thread_rng());
+        slots.sort_by_key(|t| self.x.events[*t].len());
+
+        for t in slots {
+            if let Some(rooms) = self.fits(e, t) {
+                self.x.events[t] = rooms;
+                self.slot_of[e.0] = Some(t);
+                return;
+            }
+        }
+
+        self.x.eject_list.push(e);
+    }
+
+    /// Reassign rooms slot by slot to shorten the distances walked
+    fn finish(mut self) -> InitialSolution {
+        let mut s = Solution::from_timemap(&self.x.events, self.project).unwrap();
+        for t in self.project.config.iter_slots() {
+            if let Some(rooms) = assignment::assign_slot(&s, t, self.project) {
+                s.set_slot(t, rooms, self.project);
+            }
+        }
+
+        self.x.events = s.to_timemap();
+        self.x
+    }
+}
+
+/// Colour events in order of saturation degree (DSatur), ties broken by degree
+pub fn dsatur(project: &Project) -> InitialSolution {
+    let mut c = Coloring::new(project);
+
+    let mut remaining: Vec<Event> = project.events.iter_all().collect();
+    remaining.shuffle(&mut thread_rng());
+
+    while !remaining.is_empty() {
+        let (i, _) = remaining
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, e)| (c.saturation(**e), c.degree(**e)))
+            .unwrap();
+        let e = remaining.swap_remove(i);
+
+        c.assign(e);
+    }
+
+    c.finish()
+}
+
+/// Colour events in order of decreasing degree
+pub fn largest_degree_first(project: &Project) -> InitialSolution {
+    let mut c = Coloring::new(project);
+
+    let mut order: Vec<Event> = project.events.iter_all().collect();
+    order.shuffle(&mut thread_rng());
+    order.sort_by_key(|e| std::cmp::Reverse(c.degree(*e)));
+
+    for e in order {
+        c.assign(e);
+    }
+
+    c.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_coloring_partial_is_consistent() {
+        let project = Project::parse("./converted/comp01");
+
+        for x in [dsatur(&project), largest_degree_first(&project)] {
+            let violations = crate::violations::find_violations(x.events(), &project);
+            assert_eq!(violations.len(), x.unassigned());
+            assert!(violations
+                .iter()
+                .all(|v| matches!(v, crate::violations::Violation::Missing { .. })));
+        }
+    }
+}
@@ -0,0 +1,521 @@
+// Code for initial solution
+
+mod coloring;
+
+use std::{
+    collections::VecDeque,
+    io::Write,
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use itertools::Itertools;
+use rand::{seq::SliceRandom, thread_rng};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{
+    assignment, fatal,
+    optimize::TIMEMAP,
+    project::{Event, Project, Room},
+};
+
+/// A (possibly partial) assignment with the events not yet assigned
+#[derive(Clone, Debug)]
+pub struct InitialSolution {
+    events: TIMEMAP,
+    eject_list: Vec<Event>,
+}
+
+impl InitialSolution {
+    /// Create a new solution
+    /// timeslots is
+    pub fn new(mut all_events: Vec<Event>, slots: Range<usize>) -> Self {
+        let mut events = vec![];
+
+        for _ in slots {
+            events.push(vec![]);
+        }
+
+        all_events.shuffle(&mut thread_rng());
+
+        InitialSolution {
+            events,
+            eject_list: all_events,
+        }
+    }
+
+    /// Assigned `events`, and the `unassigned` ones
+    pub fn from_parts(events: TIMEMAP, unassigned: Vec<Event>) -> Self {
+        InitialSolution {
+            events,
+            eject_list: unassigned,
+        }
+    }
+
+    pub fn unassigned(&self) -> usize {
+        self.eject_list.len()
+    }
+
+    /// Every event is assigned
+    pub fn is_complete(&self) -> bool {
+        self.eject_list.is_empty()
+    }
+
+    pub fn events(&self) -> &TIMEMAP {
+        &self.events
+    }
+
+    pub fn unassigned_events(&self) -> &[Event] {
+        &self.eject_list
+    }
+
+    pub fn into_inner(self) -> TIMEMAP {
+        self.events
+    }
+
+    /// Print the timetable and the events left over
+    pub fn print(&self, project: &Project) {
+        println!("{}", crate::utils::make_table(&self.events, project, None));
+
+        println!("Unassigned events:");
+        for e in self.eject_list.iter() {
+            println!(
+                "  {} in a {} room",
+                project.events.kind_name(e),
+                project.rooms.kind_name(&project.events.room_kind(e))
+            );
+        }
+    }
+}
+
+impl PartialEq for InitialSolution {
+    fn eq(&self, other: &Self) -> bool {
+        self.events == other.events
+    }
+}
+
+impl Eq for InitialSolution {}
+
+impl std::hash::Hash for InitialSolution {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.events.hash(state);
+    }
+}
+
+/// Gives local_best and original x
+#[inline]
+fn find_local_best(project: &Project, x: &mut InitialSolution) -> InitialSolution {
+    let mut local_best = x.clone();
+
+    let e_in = x.eject_list.pop().unwrap();
+    let e_in_kind = project.events.kind(&e_in);
+    let max_per_day = project.events.max_per_day(&e_in);
+
+    let room_kind = project.events.room_kind(&e_in);
+
+    for t in project.config.iter_slots() {
+        let mut xx = x.clone();
+        let mut events = vec![];
+
+        // Check for C3
+        while let Some((e, r)) = xx.events[t].pop() {
+            if project.events.have_people_conflict(e_in, e) {
+                // Attendee conflict
+                xx.eject_list.push(e);
+                continue;
+            }
+
+            events.push((e, r));
+        }
+        xx.events[t] = events;
+
+        // Check for C4: Evenly Distribute
+        let mut count = 0;
+        for tt in project.config.slots_of_same_day(t) {
+            let mut events = vec![];
+            while let Some((e, r)) = xx.events[tt].pop() {
+                if project.events.kind(&e) == e_in_kind {
+                    count += 1;
+                    if count >= max_per_day {
+                        // Eject
+                        xx.eject_list.push(e);
+                        continue;
+                    }
+                }
+
+                events.push((e, r));
+            }
+            xx.events[tt] = events;
+        }
+
+        // Check for C1: match the rooms of the slot, ejecting an event which
+        // needs the same kind of room if they do not suffice
+        let kept: Vec<Event> = xx.events[t].iter().map(|(e, _)| *e).collect();
+        let ejectable = kept
+            .iter()
+            .filter(|e| project.events.room_kind(e) == room_kind)
+            .map(Some);
+        for ejected in std::iter::once(None).chain(ejectable) {
+            let mut events: Vec<Event> = kept
+                .iter()
+                .filter(|e| Some(*e) != ejected)
+                .copied()
+                .collect();
+            events.push(e_in);
+
+            let rooms = match assignment::match_rooms(&events, project, |_, _| 0) {
+                Some(rooms) => rooms,
+                None => continue,
+            };
+
+            // Every hard constraints satisfied
+            let mut y = xx.clone();
+            y.eject_list.extend(ejected.copied());
+            y.events[t] = rooms;
+
+            if y.unassigned() <= local_best.unassigned() {
+                local_best = y;
+            }
+
+            if ejected.is_none() {
+                break;
+            }
+        }
+    }
+
+    x.eject_list.push(e_in);
+    local_best
+}
+
+/// Gives the best (possibly partial) solution found
+pub fn find_initial_solution_tabu(project: &Project, verbose: bool) -> InitialSolution {
+    search_tabu(project, verbose, &AtomicBool::new(false))
+}
+
+/// Gives the best (possibly partial) solution found
+pub fn find_initial_solution_constructive(project: &Project, verbose: bool) -> InitialSolution {
+    search_constructive(project, verbose, &AtomicBool::new(false))
+}
+
+/// Gives up early once `stop` is set
+fn search_tabu(project: &Project, verbose: bool, stop: &AtomicBool) -> InitialSolution {
+    let x = InitialSolution::new(
+        project.events.iter_all().collect(),
+        project.config.iter_slots(),
+    );
+    tabu_from(project, x, verbose, stop)
+}
+
+/// Tabu search for the remaining events of `x`
+fn tabu_from(
+    project: &Project,
+    mut x: InitialSolution,
+    verbose: bool,
+    stop: &AtomicBool,
+) -> InitialSolution {
+    if x.is_complete() {
+        return x;
+    }
+
+    let mut best = x.clone();
+
+    // let mut tabu: FxHashSet<InitialSolution> = FxHashSet::default();
+    let mut tabu = VecDeque::with_capacity(project.config.tabu_size);
+
+    for _ in 0..project.config.max_iter_initial {
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+
+        // let mut local_best = Mutex::new(x.clone());
+
+        // Shuffle is quite time expensive
+        // prod.shuffle(&mut thread_rng());
+
+        // prod.par_iter().for_each(|(t, r_in)| {
+        //
+
+        // let local_best = local_best.into_inner().unwrap();
+
+        let local_best = find_local_best(&project, &mut x);
+
+        if local_best.unassigned() < best.unassigned() {
+            best = local_best.clone();
+            if verbose {
+                print!("{} ", best.unassigned());
+            }
+            std::io::stdout().flush().unwrap();
+        }
+
+        if best.unassigned() == 0 {
+            break;
+        }
+
+        if !tabu.contains(&local_best) {
+            x = local_best.clone();
+            if tabu.len() == project.config.tabu_size {
+                tabu.pop_front();
+            }
+            tabu.push_back(local_best);
+        } else {
+            // Asp criterion
+        }
+    }
+
+    if verbose {
+        println!();
+    }
+
+    best
+}
+
+/// Assign the events left over in `x` without moving the others more than needed
+pub fn repair(project: &Project, x: InitialSolution) -> InitialSolution {
+    tabu_from(project, x, false, &AtomicBool::new(false))
+}
+
+/// Gives up early once `stop` is set
+fn search_constructive(project: &Project, verbose: bool, stop: &AtomicBool) -> InitialSolution {
+    let mut x = InitialSolution::new(
+        project.events.iter_all().collect(),
+        project.config.iter_slots(),
+    );
+    let mut best = x.clone();
+
+    for _ in 0..project.config.max_iter_initial {
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let local_best = find_local_best(&project, &mut x);
+
+        if local_best.unassigned() < best.unassigned() {
+            best = local_best.clone();
+            x = local_best;
+            if verbose {
+                print!("{} ", best.unassigned());
+            }
+            std::io::stdout().flush().unwrap();
+        }
+
+        if best.unassigned() == 0 {
+            break;
+        }
+    }
+
+    if verbose {
+        println!();
+    }
+
+    best
+}
+
+/// Graph colouring followed by a tabu search for the events left over
+fn search_dsatur(project: &Project, verbose: bool, stop: &AtomicBool) -> InitialSolution {
+    let x = coloring::dsatur(project);
+    if verbose {
+        println!("DSatur: {} unassigned", x.unassigned());
+    }
+    tabu_from(project, x, verbose, stop)
+}
+
+/// Graph colouring followed by a tabu search for the events left over
+fn search_largest_degree(project: &Project, verbose: bool, stop: &AtomicBool) -> InitialSolution {
+    let x = coloring::largest_degree_first(project);
+    if verbose {
+        println!("Largest degree first: {} unassigned", x.unassigned());
+    }
+    tabu_from(project, x, verbose, stop)
+}
+
+/// Gives a feasible solution, or the best partial solution of all attempts
+pub fn find_initial_solution(project: &Project, verbose: bool) -> Result<TIMEMAP, InitialSolution> {
+    find_initial_population(project, 1, verbose).map(|mut p| p.remove(0))
+}
+
+/// Gives `n` distinct feasible solutions, or the best partial solution of all attempts.
+/// Up to `initial_attempts` searches are made per solution, in parallel if `initial_parallel` is set.
+pub fn find_initial_population(
+    project: &Project,
+    n: usize,
+    verbose: bool,
+) -> Result<Vec<TIMEMAP>, InitialSolution> {
+    let f = match project.config.initial_method.as_str() {
+        "tabu" => search_tabu,
+        "constructive" => search_constructive,
+        "dsatur" => search_dsatur,
+        "largest_degree" => search_largest_degree,
+        _ => fatal!("Invalid initial method"),
+    };
+
+    if n == 0 {
+        return Ok(vec![]);
+    }
+
+    let parallel = project.config.initial_parallel;
+    let attempts = project.config.initial_attempts * n;
+
+    let stop = AtomicBool::new(false);
+    let found: Mutex<Vec<TIMEMAP>> = Mutex::new(vec![]);
+    let best: Mutex<Option<InitialSolution>> = Mutex::new(None);
+
+    let attempt = |i: usize| {
+        if stop.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // Progress of parallel searches would be interleaved
+        let s = f(project, verbose && !parallel, &stop);
+
+        if s.is_complete() {
+            let mut found = found.lock().unwrap();
+            if found.len() < n && !found.iter().any(|f| same_timemap(f, &s.events, project)) {
+                found.push(s.events);
+            }
+            if found.len() == n {
+                stop.store(true, Ordering::Relaxed);
+            }
+            return;
+        }
+
+        if verbose && !stop.load(Ordering::Relaxed) {
+            println!("Attempts {} / {} failed", i + 1, attempts);
+        }
+
+        let mut best = best.lock().unwrap();
+        if best
+            .as_ref()
+            .is_none_or(|b| s.unassigned() < b.unassigned())
+        {
+            *best = Some(s);
+        }
+    };
+
+    if parallel {
+        (0..attempts).into_par_iter().for_each(attempt);
+    } else {
+        (0..attempts).for_each(attempt);
+    }
+
+    let found = found.into_inner().unwrap();
+    if !found.is_empty() {
+        if found.len() < n && verbose {
+            println!(
+                "Found only {} / {n} distinct initial solutions",
+                found.len()
+            );
+        }
+        return Ok(found);
+    }
+
+    Err(best.into_inner().unwrap().unwrap_or_else(|| {
+        InitialSolution::new(
+            project.events.iter_all().collect(),
+            project.config.iter_slots(),
+        )
+    }))
+}
+
+/// Same kinds of events in the same rooms of each slot. Events of a kind are
+/// interchangeable, swapping them gives the same timetable.
+fn same_timemap(a: &TIMEMAP, b: &TIMEMAP, project: &Project) -> bool {
+    let kinds = |v: &Vec<(Event, Room)>| -> Vec<(usize, usize)> {
+        v.iter()
+            .map(|(e, r)| (r.0, project.events.kind(e).0))
+            .sorted()
+            .collect()
+    };
+    a.iter().zip(b).all(|(a, b)| kinds(a) == kinds(b))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::violations::find_violations;
+
+    use super::*;
+
+    #[test]
+    fn test_find_initial_solution_tabu() {
+        let project = Project::parse("./demo");
+
+        for _ in 0..5 {
+            let s = find_initial_solution_tabu(&project, true);
+            if s.is_complete() {
+                let violations = find_violations(s.events(), &project);
+                assert!(violations.is_empty(), "{violations}");
+                return;
+            }
+        }
+
+        panic!("find_initial_solution_tabu failed")
+    }
+
+    #[test]
+    fn test_find_initial_solution_constructive() {
+        let project = Project::parse("./demo");
+
+        for _ in 0..5 {
+            let s = find_initial_solution_constructive(&project, true);
+            if s.is_complete() {
+                let violations = find_violations(s.events(), &project);
+                assert!(violations.is_empty(), "{violations}");
+                return;
+            }
+        }
+
+        panic!("find_initial_solution_constructive failed")
+    }
+
+    #[test]
+    fn test_partial_solution_is_consistent() {
+        let project = Project::parse("./converted/comp01");
+
+        let s = find_initial_solution_constructive(&project, false);
+        let violations = find_violations(s.events(), &project);
+
+        assert_eq!(violations.len(), s.unassigned());
+        assert!(violations
+            .iter()
+            .all(|v| matches!(v, crate::violations::Violation::Missing { .. })));
+    }
+
+    #[test]
+    fn test_same_timemap() {
+        let project = Project::parse("./converted/comp01");
+        let kind = project.events.kind(&Event(0));
+        let same_kind: Vec<Event> = project.events.events_with_kind(kind).into_iter().collect();
+        let other = project
+            .events
+            .iter_all()
+            .find(|e| project.events.kind(e) != kind)
+            .unwrap();
+
+        let a = vec![vec![(same_kind[0], Room(0))], vec![(same_kind[1], Room(1))]];
+        let b = vec![vec![(same_kind[1], Room(0))], vec![(same_kind[0], Room(1))]];
+        let c = vec![vec![(same_kind[0], Room(0))], vec![(other, Room(1))]];
+        assert!(same_timemap(&a, &b, &project));
+        assert!(!same_timemap(&a, &c, &project));
+    }
+
+    #[test]
+    fn test_find_initial_population_parallel() {
+        let mut project = Project::parse("./converted/comp01");
+        project.config.initial_parallel = true;
+
+        let population = find_initial_population(&project, 2, false).unwrap();
+
+        assert_eq!(population.len(), 2);
+        assert!(!same_timemap(&population[0], &population[1], &project));
+
+        assert!(find_initial_population(&project, 0, false)
+            .unwrap()
+            .is_empty());
+        for s in population {
+            let violations = find_violations(&s, &project);
+            assert!(violations.is_empty(), "{violations}");
+        }
+    }
+}
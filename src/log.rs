@@ -1,14 +1,13 @@
-use std::{io::Write, path::Path};
+use std::{io::Write, ops::ControlFlow, path::Path};
 
 use serde::Serialize;
 
 use crate::{
+    observer::Observer,
     optimize::{Solution, TIMEMAP},
     project::Project,
 };
 
-static mut LOG: Option<Log> = None;
-
 pub fn now_ms() -> u128 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -16,66 +15,111 @@ pub fn now_ms() -> u128 {
         .as_millis()
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct Step {
     pub i: usize,
+    pub island: usize,
+    /// Duration of the iteration, in ms
+    pub time: u128,
+    pub population_size: usize,
     pub weights: Vec<f32>,
     pub neighborhood_average: Vec<f32>,
     pub history_size: usize,
+    /// Solutions of the population never visited before
+    pub novel: usize,
+    /// Solutions of the population already in the history
+    pub revisited: usize,
+    /// Fraction of the graded neighbors not in the history
+    pub graded_novelty: f32,
+    pub archive_size: usize,
     pub neighborhood_grading_time: u128,
     pub mosa_time: u128,
     pub average_scores: Vec<f32>,
     pub max_scores: Vec<f32>,
     pub graded: usize,
     pub temperature: f32,
+    /// Of the population, see `pareto::metrics`. Only with `metrics` in config.json
+    pub hypervolume: Option<f64>,
+    /// Of the population against `igd_reference` of config.json
+    pub igd: Option<f32>,
+    pub spread: Option<f32>,
 }
 
+/// Keeps everything, to be written to log.json
 #[derive(Default, Debug, Serialize)]
-struct Log {
+pub struct JsonLog {
+    /// Names of the objectives, in the order of the scores
+    objectives: Vec<String>,
+    /// Names of the criteria, in the order of the criteria scores
+    criteria: Vec<String>,
+
     initial_method: String,
     initial_time: u128,
     initial_scores: Vec<f32>,
+    initial_criteria_scores: Vec<f32>,
 
     steps: Vec<Step>,
     solutions: Vec<TIMEMAP>,
     solutions_scores: Vec<Vec<f32>>,
+    solutions_criteria_scores: Vec<Vec<f32>>,
 }
 
-pub fn initial(project: &Project, time: u128, s: TIMEMAP) {
-    unsafe {
-        if LOG.is_none() {
-            LOG = Some(Log::default());
-        }
-        let log = LOG.as_mut().unwrap();
-        log.initial_method = project.config.initial_method.to_owned();
-        log.initial_time = time;
-        log.initial_scores = project.criteria().evaluate(&Solution::new(s), project);
+impl Observer for JsonLog {
+    fn on_initial(&mut self, project: &Project, time: u128, s: &TIMEMAP) -> ControlFlow<()> {
+        self.initial_method = project.config.initial_method.to_owned();
+        self.initial_time = time;
+        let criteria = project.criteria();
+        self.objectives = criteria
+            .objectives()
+            .iter()
+            .map(|o| o.name.clone())
+            .collect();
+        self.criteria = criteria.labels().to_vec();
+        self.initial_criteria_scores =
+            criteria.evaluate_criteria(&Solution::from_timemap(s, project).unwrap(), project);
+        self.initial_scores = criteria.aggregate(&self.initial_criteria_scores);
+        ControlFlow::Continue(())
     }
-}
 
-pub fn step(step: Step) {
-    unsafe {
-        let log = LOG.as_mut().unwrap();
-        log.steps.push(step);
+    fn on_step(&mut self, step: &Step, _front: &[Vec<f32>]) -> ControlFlow<()> {
+        self.steps.push(step.clone());
+        ControlFlow::Continue(())
     }
-}
 
-pub fn finish<P: AsRef<Path>>(project: &Project, path: P, solutions: Vec<TIMEMAP>) {
-    unsafe {
-        let log = LOG.as_mut().unwrap();
-        log.solutions = solutions.clone();
-        log.solutions_scores = solutions
+    fn on_finish(&mut self, project: &Project, solutions: &[TIMEMAP]) {
+        self.solutions = solutions.to_vec();
+        self.solutions_criteria_scores = solutions
             .iter()
             .map(|s| {
                 project
                     .criteria()
-                    .evaluate(&Solution::new(s.clone()), project)
+                    .evaluate_criteria(&Solution::from_timemap(s, project).unwrap(), project)
             })
             .collect();
+        self.solutions_scores = self
+            .solutions_criteria_scores
+            .iter()
+            .map(|s| project.criteria().aggregate(s))
+            .collect();
+    }
+}
 
-        let json = serde_json::to_string(log).unwrap();
+impl JsonLog {
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Write log.json and the solutions to solutions.json in the project directory `path`
+    pub fn write<P: AsRef<Path>>(&self, project: &Project, path: P) {
+        let json = serde_json::to_string(self).unwrap();
 
         let mut file = std::fs::File::create(path.as_ref().join("log.json")).unwrap();
         file.write_all(json.as_bytes()).unwrap();
+
+        crate::export::write(
+            path.as_ref().join("solutions.json"),
+            &self.solutions,
+            project,
+        );
     }
 }
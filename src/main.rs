@@ -5,17 +5,34 @@ use tikv_jemallocator::Jemalloc;
 #[global_allocator]
 static GLOBAL: Jemalloc = Jemalloc;
 
-mod criteria;
+pub mod adaptive;
+pub mod assignment;
+pub mod criteria;
+pub mod crossover;
+pub mod diagnosis;
+#[cfg(feature = "exact")]
+pub mod exact;
+pub mod export;
+pub mod history;
 pub mod initial;
 pub mod log;
 pub mod neighborhoods;
+pub mod observer;
 pub mod optimize;
+pub mod optimizer;
 pub mod pareto;
 pub mod project;
 mod tsp;
 mod utils;
+pub mod violations;
 
-use clap::Parser;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use clap::{Parser, Subcommand};
+use observer::Observer;
 
 #[derive(Debug, Parser)]
 struct Args {
@@ -24,6 +41,26 @@ struct Args {
 
     #[arg(short, long)]
     only_initial: bool,
+
+    /// Print and export (to partial.json) the best partial solution if no feasible one is found
+    #[arg(long)]
+    partial: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Check exported solutions (e.g. solutions.json) against hard constraints
+    Validate { solutions: String },
+    /// Solve to optimality with the linear criteria (small instances only)
+    #[cfg(feature = "exact")]
+    Exact {
+        /// Use the exact solution as the initial solution of the optimization
+        #[arg(long)]
+        optimize: bool,
+    },
 }
 
 fn main() {
@@ -31,24 +68,82 @@ fn main() {
 
     let proj = project::Project::parse(&args.project);
 
-    let static_ref: &'static project::Project = Box::leak(Box::new(proj));
+    if let Some(Command::Validate { solutions }) = &args.command {
+        if violations::validate(solutions, &proj) > 0 {
+            std::process::exit(1);
+        }
+        return;
+    }
 
-    let s = initial::find_initial_solution(static_ref, true);
+    // Stops the optimization on Ctrl-C. Installed only then, so that Ctrl-C still
+    // ends the exact and initial searches
+    let on_ctrlc = || {
+        let interrupted = Arc::new(AtomicBool::new(false));
+        let flag = interrupted.clone();
+        ctrlc::set_handler(move || flag.store(true, Ordering::Relaxed))
+            .expect("Error setting Ctrl-C handler");
+        observer::Interrupt::new(interrupted)
+    };
 
-    if args.only_initial {
-        if let Some(s) = s {
-            println!("{}", utils::make_table(&s, static_ref, None));
+    let mut json_log = log::JsonLog::default();
+
+    #[cfg(feature = "exact")]
+    if let Some(Command::Exact { optimize }) = args.command {
+        let t0 = log::now_ms();
+        let s = match exact::solve(&proj, &[]) {
+            Ok(s) => s,
+            Err(e) => fatal!("Exact solver failed: {e}"),
+        };
+        println!("{}", utils::make_table(&s.timemap, &proj, None));
+        println!("Optimal objective: {}", s.objective);
+
+        if optimize {
+            if json_log
+                .on_initial(&proj, log::now_ms() - t0, &s.timemap)
+                .is_continue()
+            {
+                let mut observer = (&mut json_log, (observer::Progress::new(&proj), on_ctrlc()));
+                optimize::optimize_solution(vec![s.timemap], &proj, &mut observer);
+            }
+            json_log.write(&proj, args.project);
         }
         return;
     }
 
-    if let Some(s) = s {
-        let s = optimize::optimize_solution(s, static_ref);
+    let n = proj.config.initial_population.max(1);
+    let t0 = log::now_ms();
+    let s = match initial::find_initial_population(&proj, n, true) {
+        Ok(s) => s,
+        Err(partial) => {
+            warn!("No feasible initial solution found. Possible causes:");
+            print!(
+                "{}",
+                diagnosis::diagnose(&proj, partial.unassigned_events())
+            );
+
+            if args.partial {
+                partial.print(&proj);
 
-        log::finish(static_ref, args.project, s.clone());
+                let path = std::path::Path::new(&args.project).join("partial.json");
+                export::write_partial(&path, &partial, &proj);
+                println!("Exported to {}", path.display());
+            }
+            return;
+        }
+    };
+    let initial = json_log.on_initial(&proj, log::now_ms() - t0, &s[0]);
 
-        // for s in s {
-        //     println!("{}", utils::make_table(&s, static_ref, None));
-        // }
+    if args.only_initial {
+        for s in s.iter() {
+            println!("{}", utils::make_table(s, &proj, None));
+        }
+        return;
+    }
+
+    if initial.is_continue() {
+        let mut observer = (&mut json_log, (observer::Progress::new(&proj), on_ctrlc()));
+        optimize::optimize_solution(s, &proj, &mut observer);
     }
+
+    json_log.write(&proj, args.project);
 }
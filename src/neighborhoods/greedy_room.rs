@@ -49,13 +49,16 @@ pub fn neighborhoods(s: Solution, project: &Project, tx: &Sender<Solution>) {
                 .into_iter()
                 .zip(to_be_rearranged.into_iter())
             {
-                xx.events_in_slot_mut(t)
-                    .extend(other_events[t_origin].drain(..));
+                for (e, r) in other_events[t_origin].drain(..) {
+                    xx.insert(t, e, r, project);
+                }
             }
 
             // Push other unrelated events
             for (t, v) in other_events.into_iter().enumerate() {
-                xx.events_in_slot_mut(t).extend(v);
+                for (e, r) in v {
+                    xx.insert(t, e, r, project);
+                }
             }
 
             tx.send(xx).unwrap();
@@ -74,8 +77,7 @@ mod test {
 
         let solution = crate::initial::find_initial_solution(&project, true).unwrap();
 
-        let mut solution = crate::optimize::Solution::new(solution);
-        solution.fill_counter(&project);
+        let solution = crate::optimize::Solution::from_timemap(&solution, &project).unwrap();
 
         let (tx, rx) = channel();
 
@@ -86,7 +88,7 @@ mod test {
 
         dbg!(v.len());
 
-        for mut e in v {
+        for e in v {
             e.is_valid(&project).unwrap();
         }
     }
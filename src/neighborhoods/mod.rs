@@ -1,4 +1,32 @@
+use std::sync::mpsc::Sender;
+
+use crate::{optimize::Solution, project::Project};
+
 pub mod greedy_room;
 pub mod greedy_time;
 pub mod relocation;
+pub mod repair_rooms;
 pub mod swap;
+
+/// Generates valid neighbors of a solution and sends them to `tx`
+pub trait Neighborhood: Send + Sync {
+    fn neighborhoods(&self, s: Solution, project: &Project, tx: &Sender<Solution>);
+}
+
+impl<F: Fn(Solution, &Project, &Sender<Solution>) + Send + Sync> Neighborhood for F {
+    fn neighborhoods(&self, s: Solution, project: &Project, tx: &Sender<Solution>) {
+        self(s, project, tx)
+    }
+}
+
+/// Neighborhoods used by the optimizer unless others are registered
+pub fn default_neighborhoods() -> Vec<Box<dyn Neighborhood>> {
+    vec![
+        Box::new(relocation::neighborhoods),
+        Box::new(greedy_room::neighborhoods),
+        Box::new(swap::room_only),
+        Box::new(swap::time_only),
+        Box::new(swap::time_and_room),
+        Box::new(repair_rooms::neighborhoods),
+    ]
+}
@@ -26,8 +26,8 @@ pub fn neighborhoods(s: Solution, project: &Project, tx: &Sender<Solution>) {
                 }
 
                 let mut xx = s.clone();
-                xx.events_in_slot_mut(t).retain(|(e2, _)| *e2 != e);
-                xx.events_in_slot_mut(t2).push((e, r));
+                xx.remove(e, project);
+                xx.insert(t2, e, r, project);
 
                 // Produces xx
                 tx.send(xx).unwrap();
@@ -48,8 +48,7 @@ mod test {
         let sol = crate::initial::find_initial_solution(&proj, false).unwrap();
 
         dbg!(sol.len());
-        let mut sol = crate::optimize::Solution::new(sol);
-        sol.fill_counter(&proj);
+        let sol = crate::optimize::Solution::from_timemap(&sol, &proj).unwrap();
 
         let (tx, rx) = channel();
 
@@ -60,7 +59,7 @@ mod test {
 
         dbg!(solutions.len());
 
-        for mut s in solutions {
+        for s in solutions {
             s.is_valid(&proj).unwrap();
         }
 
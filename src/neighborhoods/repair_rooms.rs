@@ -0,0 +1,64 @@
+use crate::optimize::Solution;
+use crate::project::Project;
+use std::sync::mpsc::Sender;
+
+/// Reassign the rooms of one slot optimally, for every slot where it changes anything
+pub fn neighborhoods(s: Solution, project: &Project, tx: &Sender<Solution>) {
+    for t in project.config.iter_slots() {
+        let rooms = match crate::assignment::assign_slot(&s, t, project) {
+            Some(rooms) => rooms,
+            None => continue,
+        };
+
+        if rooms.iter().all(|(e, r)| s.slot_of(*e) == Some((t, *r))) {
+            continue;
+        }
+
+        let mut xx = s.clone();
+        xx.set_slot(t, rooms, project);
+
+        tx.send(xx).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::mpsc::channel;
+
+    use super::*;
+    use crate::project::Room;
+
+    #[test]
+    fn test_repair_rooms() {
+        let project = Project::parse("./converted/comp01");
+
+        // A person walks from room 0 to the farthest room between two slots,
+        // while room 0 is free in the second one
+        let p = crate::project::Person(0);
+        let mut events = project.people.events_attended_by(p).iter().copied();
+        let (e1, e2) = (events.next().unwrap(), events.next().unwrap());
+        let far = project
+            .rooms
+            .iter_all()
+            .max_by_key(|r| project.rooms.distance(&Room(0), r))
+            .unwrap();
+        assert!(project.rooms.distance(&Room(0), &far) > 0);
+
+        let mut solution = Solution::empty(&project);
+        solution.insert(0, e1, Room(0), &project);
+        solution.insert(1, e2, far, &project);
+
+        let (tx, rx) = channel();
+        neighborhoods(solution.clone(), &project, &tx);
+        drop(tx);
+
+        let v: Vec<Solution> = rx.iter().collect();
+        assert!(!v.is_empty());
+        assert!(v.iter().any(|s| s.slot_of(e2) == Some((1, Room(0)))));
+
+        for s in v {
+            assert!(s != solution);
+            assert_eq!(s.iter_all().len(), 2);
+        }
+    }
+}
@@ -15,36 +15,16 @@ pub fn room_only(s: Solution, project: &Project, tx: &Sender<Solution>) {
             let mut xx = s.clone();
 
             // Swap two events
-            xx.events_in_slot_mut(*t1).retain(|(e, _)| e != e1);
-            xx.events_in_slot_mut(*t2).retain(|(e, _)| e != e2);
+            xx.remove(*e1, project);
+            xx.remove(*e2, project);
 
             // Check only for room conflict
-            let mut flag = false;
-
-            for (_, r) in xx.events_in_slot(*t1) {
-                // Room conflict
-                if *r2 == *r {
-                    flag = true;
-                    break;
-                }
-            }
-            if flag {
-                continue;
-            }
-
-            for (_, r) in xx.events_in_slot(*t2) {
-                // Room conflict
-                if *r1 == *r {
-                    flag = true;
-                    break;
-                }
-            }
-            if flag {
+            if xx.event_in(*t1, *r2).is_some() || xx.event_in(*t2, *r1).is_some() {
                 continue;
             }
 
-            xx.events_in_slot_mut(*t1).push((*e1, *r2));
-            xx.events_in_slot_mut(*t2).push((*e2, *r1));
+            xx.insert(*t1, *e1, *r2, project);
+            xx.insert(*t2, *e2, *r1, project);
 
             tx.send(xx).unwrap();
         }
@@ -77,8 +57,8 @@ pub fn time_only(s: Solution, project: &Project, tx: &Sender<Solution>) {
             let mut xx = s.clone();
 
             // Swap two events
-            xx.events_in_slot_mut(*t1).retain(|(e, _)| e != e1);
-            xx.events_in_slot_mut(*t2).retain(|(e, _)| e != e2);
+            xx.remove(*e1, project);
+            xx.remove(*e2, project);
 
             if xx.event_can_not_fit_in(e1, r1, *t2, project) {
                 continue;
@@ -87,8 +67,8 @@ pub fn time_only(s: Solution, project: &Project, tx: &Sender<Solution>) {
                 continue;
             }
 
-            xx.events_in_slot_mut(*t1).push((*e2, *r2));
-            xx.events_in_slot_mut(*t2).push((*e1, *r1));
+            xx.insert(*t1, *e2, *r2, project);
+            xx.insert(*t2, *e1, *r1, project);
 
             tx.send(xx).unwrap();
         }
@@ -126,8 +106,8 @@ pub fn time_and_room(s: Solution, project: &Project, tx: &Sender<Solution>) {
             let mut xx = s.clone();
 
             // Swap two events
-            xx.events_in_slot_mut(*t1).retain(|(e, _)| e != e1);
-            xx.events_in_slot_mut(*t2).retain(|(e, _)| e != e2);
+            xx.remove(*e1, project);
+            xx.remove(*e2, project);
 
             if xx.event_can_not_fit_in(e1, r2, *t2, &project) {
                 continue;
@@ -136,8 +116,8 @@ pub fn time_and_room(s: Solution, project: &Project, tx: &Sender<Solution>) {
                 continue;
             }
 
-            xx.events_in_slot_mut(*t1).push((*e2, *r1));
-            xx.events_in_slot_mut(*t2).push((*e1, *r2));
+            xx.insert(*t1, *e2, *r1, project);
+            xx.insert(*t2, *e1, *r2, project);
 
             tx.send(xx).unwrap();
         }
@@ -154,20 +134,20 @@ mod test {
         let sol = crate::initial::find_initial_solution(&proj, false).unwrap();
 
         dbg!(sol.len());
-        let mut sol = crate::optimize::Solution::new(sol);
-        sol.fill_counter(&proj);
+        let sol = crate::optimize::Solution::from_timemap(&sol, &proj).unwrap();
 
         let (tx, rx) = std::sync::mpsc::channel();
 
         time_and_room(sol.clone(), &proj, &tx);
         time_only(sol.clone(), &proj, &tx);
         room_only(sol, &proj, &tx);
+        drop(tx);
 
         let solutions: Vec<Solution> = rx.iter().collect();
 
         dbg!(solutions.len());
 
-        for mut s in solutions {
+        for s in solutions {
             s.is_valid(&proj).unwrap();
         }
     }
@@ -0,0 +1,220 @@
+// Following an optimization from the outside.
+//
+// `optimize_solution` reports every iteration to an observer, which can stop the
+// run by returning `ControlFlow::Break`: the current iteration is finished and the
+// solutions found so far are returned.
+
+use std::{
+    ops::ControlFlow,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use crate::{log::Step, optimize::TIMEMAP, project::Project};
+
+pub trait Observer {
+    /// The initial solution `s`, found in `time` ms
+    fn on_initial(&mut self, _project: &Project, _time: u128, _s: &TIMEMAP) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// An iteration of an island, with the scores of its new population
+    fn on_step(&mut self, _step: &Step, _front: &[Vec<f32>]) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// A solution with `scores` entered the archive of non-dominated solutions of `island`
+    fn on_improvement(&mut self, _island: usize, _scores: &[f32]) -> ControlFlow<()> {
+        ControlFlow::Continue(())
+    }
+
+    /// The solutions of the optimization
+    fn on_finish(&mut self, _project: &Project, _solutions: &[TIMEMAP]) {}
+}
+
+impl<T: Observer + ?Sized> Observer for &mut T {
+    fn on_initial(&mut self, project: &Project, time: u128, s: &TIMEMAP) -> ControlFlow<()> {
+        (**self).on_initial(project, time, s)
+    }
+
+    fn on_step(&mut self, step: &Step, front: &[Vec<f32>]) -> ControlFlow<()> {
+        (**self).on_step(step, front)
+    }
+
+    fn on_improvement(&mut self, island: usize, scores: &[f32]) -> ControlFlow<()> {
+        (**self).on_improvement(island, scores)
+    }
+
+    fn on_finish(&mut self, project: &Project, solutions: &[TIMEMAP]) {
+        (**self).on_finish(project, solutions)
+    }
+}
+
+/// Both observers are told everything, the run stops if either asks to
+impl<A: Observer, B: Observer> Observer for (A, B) {
+    fn on_initial(&mut self, project: &Project, time: u128, s: &TIMEMAP) -> ControlFlow<()> {
+        let a = self.0.on_initial(project, time, s);
+        let b = self.1.on_initial(project, time, s);
+        either(a, b)
+    }
+
+    fn on_step(&mut self, step: &Step, front: &[Vec<f32>]) -> ControlFlow<()> {
+        let a = self.0.on_step(step, front);
+        let b = self.1.on_step(step, front);
+        either(a, b)
+    }
+
+    fn on_improvement(&mut self, island: usize, scores: &[f32]) -> ControlFlow<()> {
+        let a = self.0.on_improvement(island, scores);
+        let b = self.1.on_improvement(island, scores);
+        either(a, b)
+    }
+
+    fn on_finish(&mut self, project: &Project, solutions: &[TIMEMAP]) {
+        self.0.on_finish(project, solutions);
+        self.1.on_finish(project, solutions);
+    }
+}
+
+fn either(a: ControlFlow<()>, b: ControlFlow<()>) -> ControlFlow<()> {
+    if a.is_break() || b.is_break() {
+        ControlFlow::Break(())
+    } else {
+        ControlFlow::Continue(())
+    }
+}
+
+/// Prints a summary of each iteration
+pub struct Progress {
+    /// Prefix lines with the island
+    islands: bool,
+}
+
+impl Progress {
+    pub fn new(project: &Project) -> Self {
+        Progress {
+            islands: project.config.islands > 1,
+        }
+    }
+}
+
+impl Observer for Progress {
+    fn on_step(&mut self, step: &Step, _front: &[Vec<f32>]) -> ControlFlow<()> {
+        let island = if self.islands {
+            format!("[{}] ", step.island)
+        } else {
+            String::new()
+        };
+        println!(
+            "{island}{} in {}ms (NG: {}, MOSA: {}). Avg: {:?}. Max: {:?}.\nS: {:?}. W: {:?} T: {}. G: {}. P: {}. N: {}/{} ({}). HV: {}. IGD: {}. A: {}",
+            step.i,
+            step.time,
+            step.neighborhood_grading_time,
+            step.mosa_time,
+            step.average_scores,
+            step.max_scores,
+            step.neighborhood_average,
+            step.weights,
+            step.temperature,
+            step.graded,
+            step.population_size,
+            step.novel,
+            step.revisited,
+            step.graded_novelty,
+            step.hypervolume.map_or("-".to_owned(), |hv| format!("{hv:e}")),
+            step.igd.map_or("-".to_owned(), |igd| igd.to_string()),
+            step.archive_size
+        );
+
+        ControlFlow::Continue(())
+    }
+}
+
+/// Stops the optimization once interrupted
+pub struct Interrupt(Arc<AtomicBool>);
+
+impl Interrupt {
+    /// Stops once `interrupted` is set, e.g. by a Ctrl-C handler
+    pub fn new(interrupted: Arc<AtomicBool>) -> Self {
+        Interrupt(interrupted)
+    }
+}
+
+impl Observer for Interrupt {
+    fn on_step(&mut self, _step: &Step, _front: &[Vec<f32>]) -> ControlFlow<()> {
+        if self.0.load(Ordering::Relaxed) {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Stops after `n` steps
+    struct Stop(usize);
+
+    impl Observer for Stop {
+        fn on_step(&mut self, _step: &Step, _front: &[Vec<f32>]) -> ControlFlow<()> {
+            self.0 = self.0.saturating_sub(1);
+            if self.0 == 0 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        }
+    }
+
+    /// Counts the steps it is told of
+    #[derive(Default)]
+    struct Count(usize);
+
+    impl Observer for Count {
+        fn on_step(&mut self, _step: &Step, _front: &[Vec<f32>]) -> ControlFlow<()> {
+            self.0 += 1;
+            ControlFlow::Continue(())
+        }
+    }
+
+    #[test]
+    fn test_either() {
+        let (c, b) = (ControlFlow::Continue(()), ControlFlow::Break(()));
+        assert_eq!(either(c, c), c);
+        assert_eq!(either(c, b), b);
+        assert_eq!(either(b, c), b);
+        assert_eq!(either(b, b), b);
+    }
+
+    #[test]
+    fn test_composition() {
+        let step = Step::default();
+        let mut count = Count::default();
+        let mut observer = (&mut count, (Stop(2), Count::default()));
+
+        assert!(observer.on_step(&step, &[]).is_continue());
+        // Every observer is told of the step which stops the run
+        assert!(observer.on_step(&step, &[]).is_break());
+        assert_eq!(observer.1 .1 .0, 2);
+        assert!(observer.on_improvement(0, &[]).is_continue());
+        assert_eq!(count.0, 2);
+    }
+
+    #[test]
+    fn test_break() {
+        // A handful of events, for a quick iteration
+        let project = crate::project::test_util::tiny_project(r#"{"room_distance": [{}]}"#);
+        let initial = crate::initial::find_initial_population(&project, 1, false).unwrap();
+
+        let mut log = crate::log::JsonLog::default();
+        let mut observer = (&mut log, Stop(1));
+        let solutions = crate::optimize::optimize_solution(initial, &project, &mut observer);
+
+        assert!(!solutions.is_empty());
+        assert_eq!(log.steps().len(), 1);
+    }
+}
@@ -1,106 +1,204 @@
-use std::{borrow::BorrowMut, collections::HashSet, sync::mpsc::Sender};
+use std::sync::Arc;
 
 use itertools::Itertools;
 use rand::{
     seq::{IteratorRandom, SliceRandom},
     thread_rng,
 };
-use rayon::iter::{IntoParallelRefMutIterator, ParallelBridge, ParallelIterator};
+use rayon::iter::{
+    IntoParallelIterator, IntoParallelRefMutIterator, ParallelBridge, ParallelIterator,
+};
 
 use crate::{
-    pareto::CanDominate,
-    project::{Event, EventKind, Project, Room},
-    log::now_ms
+    adaptive,
+    criteria::Normalization,
+    crossover, fatal,
+    history::{self, History},
+    log::now_ms,
+    neighborhoods::Neighborhood,
+    observer::Observer,
+    pareto::{self, metrics, Archive, CanDominate},
+    project::{Event, EventKind, Person, Project, Room},
+    violations::{find_violations, Violation, Violations},
 };
 
-
+/// Events in each slot with their room: how timetables are read, exported and logged
 pub type TIMEMAP = Vec<Vec<(Event, Room)>>;
 
+/// A timetable where no room holds two events at once and no event is placed twice.
+/// Every change goes through `insert` and `remove`, which keep the indexes up to date.
 #[derive(Debug, Clone)]
 pub struct Solution {
-    events: TIMEMAP,
-    counter: Vec<Vec<usize>>,
+    /// Slot and room of each event
+    placement: Vec<Option<(usize, Room)>>,
+    /// Event in each room of each slot, slot after slot
+    occupancy: Vec<Option<Event>>,
+    rooms: usize,
+    /// Slots where each person attends an event, a bitset of `words` per person
+    busy: Vec<u64>,
+    words: usize,
+    /// Number of events of each kind on each day, day after day
+    counter: Vec<usize>,
+    kinds: usize,
+    /// Zobrist hash of the placements
+    hash: u64,
 }
 
 impl PartialEq for Solution {
     fn eq(&self, other: &Self) -> bool {
-        self.events.eq(&other.events)
+        self.hash == other.hash && self.placement == other.placement
     }
 }
 
 impl Solution {
-    pub fn new(events: TIMEMAP) -> Solution {
+    /// Empty timetable
+    pub fn empty(project: &Project) -> Solution {
+        let slots = project.config.iter_slots().len();
+        let words = slots.div_ceil(64);
+
         Solution {
-            events,
-            counter: vec![],
+            placement: vec![None; project.events.len()],
+            occupancy: vec![None; slots * project.rooms.len()],
+            rooms: project.rooms.len(),
+            busy: vec![0; project.people.len() * words],
+            words,
+            counter: vec![0; project.config.days().len() * project.events.kinds_len()],
+            kinds: project.events.kinds_len(),
+            hash: 0,
         }
     }
 
-    pub fn iter_all(&self) -> Vec<(usize, Event, Room)> {
-        let mut events = vec![];
+    /// Import a timetable. Room clashes, people clashes and duplicate events can not
+    /// be represented and are given back as violations.
+    pub fn from_timemap(events: &TIMEMAP, project: &Project) -> Result<Solution, Violations> {
+        let violations: Vec<Violation> = find_violations(events, project)
+            .0
+            .into_iter()
+            .filter(|v| {
+                matches!(
+                    v,
+                    Violation::RoomClash { .. }
+                        | Violation::PeopleClash { .. }
+                        | Violation::Duplicate { .. }
+                )
+            })
+            .collect();
+        if !violations.is_empty() {
+            return Err(Violations(violations));
+        }
 
-        for (i, v) in self.events.iter().enumerate() {
+        let mut s = Solution::empty(project);
+        for (t, v) in events.iter().enumerate() {
             for (e, r) in v {
-                events.push((i, *e, *r));
+                s.insert(t, *e, *r, project);
             }
         }
+        Ok(s)
+    }
 
-        events
+    /// Events in each slot, in the order of the rooms
+    pub fn to_timemap(&self) -> TIMEMAP {
+        self.occupancy
+            .chunks(self.rooms)
+            .map(|slot| {
+                slot.iter()
+                    .enumerate()
+                    .filter_map(|(r, e)| e.map(|e| (e, Room(r))))
+                    .collect()
+            })
+            .collect()
     }
 
-    pub fn inner(&self) -> &TIMEMAP {
-        &self.events
+    /// Identifies the timetable
+    pub fn hash(&self) -> u64 {
+        self.hash
     }
 
-    pub fn into_inner(self) -> TIMEMAP {
-        self.events
+    /// Place `e` in slot `t` and room `r`, which must be free, as its attendees
+    pub fn insert(&mut self, t: usize, e: Event, r: Room, project: &Project) {
+        assert!(self.placement[e.0].is_none() && self.event_in(t, r).is_none());
+        debug_assert!(project
+            .events
+            .event_attendees(e)
+            .iter()
+            .all(|p| !self.is_busy(*p, t)));
+        self.placement[e.0] = Some((t, r));
+        self.occupancy[t * self.rooms + r.0] = Some(e);
+        for p in project.events.event_attendees(e) {
+            self.busy[p.0 * self.words + t / 64] |= 1 << (t % 64);
+        }
+        self.counter[project.config.slots_to_day(t) * self.kinds + project.events.kind(&e).0] += 1;
+        self.hash ^= history::zobrist(e, t, r);
     }
 
-    pub fn iter_all_shuffle(&self) -> Vec<(usize, Event, Room)> {
-        let mut all = self.iter_all();
+    /// Take `e` out of the timetable, giving where it was
+    pub fn remove(&mut self, e: Event, project: &Project) -> Option<(usize, Room)> {
+        let (t, r) = self.placement[e.0].take()?;
+        self.occupancy[t * self.rooms + r.0] = None;
+        for p in project.events.event_attendees(e) {
+            self.busy[p.0 * self.words + t / 64] &= !(1 << (t % 64));
+        }
+        self.counter[project.config.slots_to_day(t) * self.kinds + project.events.kind(&e).0] -= 1;
+        self.hash ^= history::zobrist(e, t, r);
+        Some((t, r))
+    }
 
-        all.shuffle(&mut thread_rng());
+    /// Replace the events of slot `t`
+    pub fn set_slot(&mut self, t: usize, events: Vec<(Event, Room)>, project: &Project) {
+        self.events_in_slot_drain(t, project);
+        for (e, r) in events {
+            self.insert(t, e, r, project);
+        }
+    }
 
-        all
+    /// Slot and room of `e`, if placed
+    pub fn slot_of(&self, e: Event) -> Option<(usize, Room)> {
+        self.placement[e.0]
     }
 
-    // Fill counter using events
-    pub fn fill_counter(&mut self, project: &Project) {
-        self.counter.clear();
-        for day in project.config.days() {
-            let mut counter_per_type = vec![0; project.events.kinds_len()];
+    /// Event in room `r` at slot `t`
+    pub fn event_in(&self, t: usize, r: Room) -> Option<Event> {
+        self.occupancy[t * self.rooms + r.0]
+    }
 
-            for t in project.config.slots_of_day(day) {
-                for (e, _) in &self.events[t] {
-                    counter_per_type[project.events.kind(e).0] += 1;
-                }
-            }
+    /// Whether `p` attends an event at slot `t`
+    pub fn is_busy(&self, p: Person, t: usize) -> bool {
+        self.busy[p.0 * self.words + t / 64] & (1 << (t % 64)) != 0
+    }
 
-            self.counter.push(counter_per_type);
-        }
+    pub fn iter_all(&self) -> Vec<(usize, Event, Room)> {
+        self.occupancy
+            .iter()
+            .enumerate()
+            .filter_map(|(i, e)| e.map(|e| (i / self.rooms, e, Room(i % self.rooms))))
+            .collect()
     }
 
-    pub fn same_kind_events(&self, day: usize, kind: EventKind) -> usize {
-        self.counter[day][kind.0]
+    pub fn iter_all_shuffle(&self) -> Vec<(usize, Event, Room)> {
+        let mut all = self.iter_all();
+
+        all.shuffle(&mut thread_rng());
+
+        all
     }
 
-    pub fn events_in_slot(&self, t: usize) -> &Vec<(Event, Room)> {
-        &self.events[t]
+    pub fn same_kind_events(&self, day: usize, kind: EventKind) -> usize {
+        self.counter[day * self.kinds + kind.0]
     }
 
-    pub fn events_in_slot_mut(&mut self, t: usize) -> &mut Vec<(Event, Room)> {
-        self.events[t].borrow_mut()
+    pub fn events_in_slot(&self, t: usize) -> impl Iterator<Item = (Event, Room)> + '_ {
+        self.occupancy[t * self.rooms..(t + 1) * self.rooms]
+            .iter()
+            .enumerate()
+            .filter_map(|(r, e)| e.map(|e| (e, Room(r))))
     }
 
     pub fn events_of_day(&self, day: usize, project: &Project) -> Vec<(usize, Event, Room)> {
-        let mut events: Vec<(usize, Event, Room)> = vec![];
-
-        for t in project.config.slots_of_day(day) {
-            // events.extend(self.events_in_slot_mut(t).iter().map(|(e, r)| (t, *e, *r)));
-            events.extend(self.events[t].iter().map(|(e, r)| (t, *e, *r)));
-        }
-
-        events
+        project
+            .config
+            .slots_of_day(day)
+            .flat_map(|t| self.events_in_slot(t).map(move |(e, r)| (t, e, r)))
+            .collect()
     }
 
     pub fn events_of_day_drain(
@@ -108,212 +206,213 @@ impl Solution {
         day: usize,
         project: &Project,
     ) -> Vec<(usize, Event, Room)> {
-        let mut events: Vec<(usize, Event, Room)> = vec![];
-
-        for t in project.config.slots_of_day(day) {
-            // events.extend(self.events_in_slot_mut(t).iter().map(|(e, r)| (t, *e, *r)));
-            events.extend(self.events[t].drain(..).map(|(e, r)| (t, e, r)));
+        let events = self.events_of_day(day, project);
+        for (_, e, _) in events.iter() {
+            self.remove(*e, project);
         }
-
         events
     }
 
-    pub fn events_in_slot_drain(&mut self, t: usize) -> Vec<(Event, Room)> {
-        self.events[t].drain(..).collect()
+    pub fn events_in_slot_drain(&mut self, t: usize, project: &Project) -> Vec<(Event, Room)> {
+        let events: Vec<(Event, Room)> = self.events_in_slot(t).collect();
+        for (e, _) in events.iter() {
+            self.remove(*e, project);
+        }
+        events
     }
 
     pub fn event_can_not_fit_in(&self, e: &Event, r: &Room, t: usize, project: &Project) -> bool {
-        for (e2, r2) in self.events_in_slot(t) {
-            // Room conflict
-            if *r2 == *r {
-                return true;
-            }
-
-            // People conflict
-            if project.events.have_people_conflict(*e, *e2) {
-                return true;
-            }
+        // Room conflict
+        if self.event_in(t, *r).is_some() {
+            return true;
         }
 
-        false
+        // People conflict
+        project
+            .events
+            .event_attendees(*e)
+            .iter()
+            .any(|p| self.is_busy(*p, t))
     }
 
-    pub fn is_valid(&mut self, project: &Project) -> Result<(), String> {
-        self.fill_counter(project);
-        let mut seen = HashSet::new();
-
-        for (_, e, _) in self.iter_all() {
-            if seen.contains(&e) {
-                return Err(format!("Multiple event: {:?}", e));
-            }
-            seen.insert(e);
-        }
-
-        if seen.len() != project.events.len() {
-            return Err(format!(
-                "Some events missing: {}",
-                project.events.len() - seen.len()
-            ));
-        }
-
-        let mut event_kind_and_max_per_day = HashSet::new();
-
-        for t in project.config.iter_slots() {
-            for (e1, r1) in self.events_in_slot(t).iter() {
-                event_kind_and_max_per_day
-                    .insert((project.events.kind(e1), project.events.max_per_day(e1)));
-
-                for (e2, r2) in self.events_in_slot(t).iter() {
-                    if e1 == e2 {
-                        continue;
-                    }
-
-                    if r1 == r2 {
-                        // return false;
-                        return Err(format!(
-                            "Room conflict at {t}: {:?} {:?} for {:?}",
-                            e1, e2, r1
-                        ));
-                    }
-
-                    if project.events.have_people_conflict(*e1, *e2) {
-                        return Err(format!("People conflict at {t}: {:?} {:?}", e1, e2));
-                    }
-                }
-            }
-        }
-
-        for day in project.config.days() {
-            for (k, m) in event_kind_and_max_per_day.iter() {
-                let n = self.same_kind_events(day, *k);
-                if n > *m {
-                    return Err(format!("Excess num of {:?} on day {day}: {n} > {m}", k));
-                }
-            }
+    pub fn is_valid(&self, project: &Project) -> Result<(), Violations> {
+        let violations = self.violations(project);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
         }
+    }
 
-        Ok(())
+    /// Find every violated hard constraint
+    pub fn violations(&self, project: &Project) -> Violations {
+        find_violations(&self.to_timemap(), project)
     }
 }
 
-pub fn optimize(initial: TIMEMAP) -> TIMEMAP {
-    let s = Solution {
-        events: initial,
-        counter: vec![],
-    };
+/// Source of the children of a crossover, which no neighborhood is credited for
+pub const CROSSOVER: usize = usize::MAX;
+
+/// A neighbor with its scores and the neighborhood it comes from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Graded {
+    pub scores: Vec<f32>,
+    pub source: usize,
+    pub solution: Solution,
+    /// Weighted scores, normalised if enabled. Their sum is the energy of the MOSA
+    /// and the objective of the weighted selection
+    pub normalized: Vec<f32>,
+    /// Of the criteria, shared by every graded solution of a run
+    epsilon: Arc<[f32]>,
+}
 
-    s.events
+#[cfg(test)]
+impl Graded {
+    /// A graded solution with the same raw and normalised scores
+    pub fn with_scores(scores: Vec<f32>, solution: Solution) -> Self {
+        let epsilon = vec![0.0; scores.len()].into();
+        Graded {
+            normalized: scores.clone(),
+            scores,
+            source: 0,
+            solution,
+            epsilon,
+        }
+    }
 }
 
-impl CanDominate for (Vec<f32>, usize, Solution) {
+impl CanDominate for Graded {
     fn sum(&self) -> f32 {
-        self.0.iter().sum()
+        self.normalized.iter().sum()
     }
 
-    fn compare_first_element(&self, other: &Self) -> std::cmp::Ordering {
-        self.0[0].partial_cmp(&other.0[0]).unwrap()
+    fn compare_lexicographic(&self, other: &Self) -> std::cmp::Ordering {
+        pareto::compare_epsilon(&self.scores, &other.scores, &self.epsilon)
     }
 
     fn dominates(&self, other: &Self) -> bool {
-        for (a, b) in self.0.iter().zip(&other.0) {
-            if b >= a {
-                return false;
-            }
-        }
-        true
+        pareto::epsilon_dominates(&self.scores, &other.scores, &self.epsilon)
     }
-}
 
-fn collect_neighborhoods(
-    f: &fn(Solution, &Project, &Sender<Solution>),
-    s: Solution,
-    project: &Project,
-) -> Vec<Solution> {
-    let (tx, rx) = std::sync::mpsc::channel();
-    f(s, project, &tx);
-    drop(tx);
-    rx.into_iter().collect()
-}
-
-const NEIGHBORHOODS: [fn(Solution, &Project, &Sender<Solution>); 5] = [
-    crate::neighborhoods::relocation::neighborhoods,
-    crate::neighborhoods::greedy_room::neighborhoods,
-    crate::neighborhoods::swap::room_only,
-    crate::neighborhoods::swap::time_only,
-    crate::neighborhoods::swap::time_and_room,
-];
-
-fn softmax_inplace(x: &mut [f32]) {
-    let mut sum = 0.0;
-    for e in x.iter() {
-        sum += e.exp();
+    fn objectives(&self) -> &[f32] {
+        &self.scores
     }
-    for e in x.iter_mut() {
-        *e = e.exp() / sum;
-    }
-}
 
-fn avg_inplace(x: &mut [f32]) {
-    let mut sum = 0.0;
-    for e in x.iter() {
-        sum += e;
-    }
-    for e in x.iter_mut() {
-        *e = *e / sum;
+    fn sort_keys(&self) -> Vec<f32> {
+        pareto::boxes(&self.scores, &self.epsilon)
     }
 }
 
+/// Feasible children of random pairs of the population, if a crossover is configured
+fn breed(population: &[Solution], project: &Project) -> Vec<Solution> {
+    if project.config.crossover == "none" || population.len() < 2 {
+        return vec![];
+    }
 
+    (0..project.config.crossover_children)
+        .into_par_iter()
+        .filter_map(|_| {
+            let pair: Vec<&Solution> = population.choose_multiple(&mut thread_rng(), 2).collect();
+            crossover::crossover(
+                &pair[0].to_timemap(),
+                &pair[1].to_timemap(),
+                &project.config.crossover,
+                project,
+            )
+        })
+        .map(|events| Solution::from_timemap(&events, project).unwrap())
+        .collect()
+}
 
-pub fn optimize_solution(s: TIMEMAP, project: &'static Project) -> Vec<TIMEMAP> {
-    let mut population = vec![Solution::new(s)];
-
-    let population_size = 20;
-    let mut temp = 1000.0;
-
-    let avg = 1.0 / NEIGHBORHOODS.len() as f32;
-
-    let penalty_thres = project.config.penalty_threshold;
-    let decay_constant = penalty_thres / project.config.penalty_factor;
-    let complementary_factor = avg * decay_constant;
+fn collect_neighborhoods(f: &dyn Neighborhood, s: Solution, project: &Project) -> Vec<Solution> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    f.neighborhoods(s, project, &tx);
+    drop(tx);
+    rx.into_iter().collect()
+}
 
-    let warmup = 0;
+/// One population evolving with its own strategy, neighborhood weights and archive
+struct Island {
+    id: usize,
+    population: Vec<Solution>,
+    strategy: Box<dyn crate::optimizer::Strategy>,
+
+    policy: Box<dyn adaptive::Policy>,
+    /// Fraction of the neighbors of each neighborhood graded
+    factored_weights: Vec<f32>,
+
+    history: History,
+    archive: Archive<Graded>,
+    hv_reference: Option<Vec<f32>>,
+    epsilon: Arc<[f32]>,
+    normalization: Normalization,
+}
 
-    let mut factored_weights = vec![avg; NEIGHBORHOODS.len()];
-    let mut last_weights = factored_weights.clone();
+impl Island {
+    fn new(id: usize, population: Vec<Solution>, project: &Project) -> Self {
+        let avg = 1.0 / project.neighborhoods.len() as f32;
+        let mut factored_weights = vec![avg; project.neighborhoods.len()];
+        if id > 0 {
+            // Other islands explore other neighborhoods first
+            for w in factored_weights.iter_mut() {
+                *w = rand::random::<f32>() + 0.1;
+            }
+            adaptive::normalize(&mut factored_weights);
+        }
 
-    let expect_graded_num = project.config.expected_graded_num;
+        Island {
+            id,
+            population,
+            strategy: crate::optimizer::from_config(project, id),
+            policy: adaptive::from_config(project, factored_weights.clone()),
+            factored_weights,
+            history: History::with_capacity(project.config.history_size),
+            archive: Archive::with_capacity(project.config.archive_size),
+            hv_reference: project.config.hypervolume_reference.clone(),
+            epsilon: project.criteria().epsilons().into(),
+            normalization: Normalization::new(project.criteria()),
+        }
+    }
 
-    let mut history = HashSet::new();
-    let history_max_size = project.config.history_size;
+    /// Take non-dominated solutions of another island
+    fn immigrate(&mut self, immigrants: Vec<Graded>) {
+        self.archive.update_from(immigrants.iter());
+        self.population
+            .extend(immigrants.into_iter().map(|g| g.solution));
+    }
 
-    let (ctrlc_send, ctrlc_recv) = std::sync::mpsc::channel();
-    ctrlc::set_handler(move || {
-        ctrlc_send.send(()).unwrap();
-    }).expect("Error setting Ctrl-C handler");
+    /// Random elite solutions, for another island
+    fn emigrants(&self, n: usize) -> Vec<Graded> {
+        self.archive
+            .members()
+            .choose_multiple(&mut thread_rng(), n)
+            .cloned()
+            .collect()
+    }
 
-    for i in 0..50 {
-        if ctrlc_recv.try_recv().is_ok() {
-            break;
-        }
+    fn step(&mut self, i: usize, project: &Project) -> Iteration {
+        let warmup = 0;
+        let expect_graded_num = project.config.expected_graded_num;
 
         let t00 = now_ms();
         let (tx, rx) = std::sync::mpsc::channel();
 
-        population
-            .par_iter_mut()
-            .for_each(|s| s.fill_counter(project));
+        let population = std::mem::take(&mut self.population);
+
+        let children = breed(&population, project);
 
         let t0 = now_ms();
-        let neighborhoods: Vec<(Vec<f32>, usize, Solution)> = population
-            .into_iter()
-            .cartesian_product(NEIGHBORHOODS.iter().enumerate())
+        let factored_weights = &self.factored_weights;
+        let epsilon = &self.epsilon;
+        let mut neighborhoods: Vec<Graded> = population
+            .iter()
+            .cartesian_product(project.neighborhoods.iter().enumerate())
             .par_bridge()
             .flat_map(move |(s, (i, f))| {
-                let n = collect_neighborhoods(f, s, project);
+                let n = collect_neighborhoods(f.as_ref(), s.clone(), project);
                 let size = n.len() as f32;
                 tx.send((i, size)).unwrap();
-            
+
                 n.into_iter()
                     .choose_multiple(
                         &mut thread_rng(),
@@ -323,178 +422,361 @@ pub fn optimize_solution(s: TIMEMAP, project: &'static Project) -> Vec<TIMEMAP>
                     .map(|s| (i, s))
                     .collect::<Vec<(usize, Solution)>>()
             })
+            .chain(children.into_par_iter().map(|s| (CROSSOVER, s)))
             .map(|(i, s)| {
-                let score = project.criteria().evaluate(&s, project);
-                (score, i, s)
+                let scores = project.criteria().evaluate(&s, project);
+                Graded {
+                    normalized: scores.clone(),
+                    scores,
+                    source: i,
+                    solution: s,
+                    epsilon: epsilon.clone(),
+                }
             })
             .collect();
+
+        if project.config.normalize {
+            for g in neighborhoods.iter() {
+                self.normalization.observe(&g.scores);
+            }
+            let normalization = &self.normalization;
+            neighborhoods
+                .par_iter_mut()
+                .for_each(|g| g.normalized = normalization.apply(&g.scores));
+        }
         let time_grading = now_ms() - t0;
 
-        let mut neighborhood_sizes = vec![0.0; NEIGHBORHOODS.len()];
+        let mut neighborhood_sizes = vec![0.0; project.neighborhoods.len()];
         for (i, size) in rx {
             neighborhood_sizes[i] += size;
         }
 
         let graded_num = neighborhoods.len();
+        let graded_novel = neighborhoods
+            .iter()
+            .filter(|g| !self.history.contains(g.solution.hash()))
+            .count();
+
+        let improvements: Vec<Vec<f32>> = self
+            .archive
+            .update_from(neighborhoods.iter())
+            .into_iter()
+            .map(|k| neighborhoods[k].scores.clone())
+            .collect();
+
+        let hv_reference = self.hv_reference.get_or_insert_with(|| {
+            let mut worst = vec![f32::MAX; project.criteria().len()];
+            for g in neighborhoods.iter() {
+                for (w, s) in worst.iter_mut().zip(&g.scores) {
+                    *w = w.min(*s);
+                }
+            }
+            // Slightly worse, so that no objective has an empty range
+            worst.iter().map(|w| w - 1.0).collect()
+        });
 
         let t0 = now_ms();
-        let frontline = crate::pareto::random_mosa(neighborhoods, population_size, temp);
+        let frontline = self.strategy.select(neighborhoods, &self.archive);
         let time_mosa = now_ms() - t0;
 
+        // Nothing to move to: stay with the current population
+        if frontline.is_empty() {
+            self.population = population;
+        }
+
         // Count scores for each neighborhoods
         let mut max_scores = vec![f32::MIN; project.criteria().len()];
         let mut sum_scores = vec![0.0f32; project.criteria().len()];
 
-        let mut neighborhoods_scores = vec![1.0f32; NEIGHBORHOODS.len()];
+        // New solutions brought by each neighborhood
+        let mut novel = vec![0.0f32; project.neighborhoods.len()];
 
-        population = vec![];
+        let mut front = vec![];
+        let mut novel_num = 0;
+        let mut revisited = 0;
 
-        for (scores, source, solution) in frontline {
+        for Graded {
+            scores,
+            source,
+            solution,
+            ..
+        } in frontline
+        {
             // Fill max and sum scores
             for (i, score) in scores.iter().enumerate() {
                 max_scores[i] = max_scores[i].max(*score);
                 sum_scores[i] += score;
             }
 
-            if history.contains(solution.inner()) {
-                // neighborhoods_scores[source] += 0.1; // Repeat, but optimal
+            if self.history.visit(solution.hash()) {
+                novel_num += 1;
+                if let Some(n) = novel.get_mut(source) {
+                    *n += 1.0;
+                }
             } else {
-                neighborhoods_scores[source] += 2.0; // New
-                history.insert(solution.clone().into_inner());
+                revisited += 1;
             }
 
-            population.push(solution);
+            self.population.push(solution);
+            front.push(scores);
         }
 
+        let config = &project.config;
+        let (hypervolume, igd, spread) = if config.metrics {
+            let igd = config.igd_reference.as_ref();
+            (
+                metrics::hypervolume(&front, hv_reference),
+                igd.map(|reference| metrics::inverted_generational_distance(&front, reference)),
+                Some(metrics::spread(&front)),
+            )
+        } else {
+            (None, None, None)
+        };
 
-        let pop_size = population.len() as f32;
-        let avg_scores: Vec<f32> = sum_scores
-            .into_iter()
-            .map(|s| s / pop_size)
-            .collect();
+        let pop_size = self.population.len() as f32;
+        let avg_scores: Vec<f32> = sum_scores.into_iter().map(|s| s / pop_size).collect();
 
+        let graded_novelty = graded_novel as f32 / graded_num.max(1) as f32;
 
-        if history.len() > history_max_size {
-            // Trim half, randomly
-            let target_size = history_max_size / 2;
-            println!(
-                "Trim history {} > {} => {}",
-                history.len(),
-                history_max_size,
-                target_size
-            );
-            let mut v = history.into_iter().collect::<Vec<_>>();
-            v.shuffle(&mut thread_rng());
-            history = v.into_iter().take(target_size).collect();
-        }
+        let average_scores = adaptive::reward_rates(&novel, &neighborhood_sizes);
+        self.policy.update(&novel, &neighborhood_sizes);
 
+        let probabilities = self.policy.probabilities();
+        self.factored_weights = if i > warmup {
+            adaptive::sampling_rates(&probabilities, &neighborhood_sizes, expect_graded_num)
+        } else {
+            probabilities
+        };
+        let weights = self.policy.weights();
 
-        dbg!(&neighborhood_sizes, &neighborhoods_scores);
+        let step = crate::log::Step {
+            i,
+            island: self.id,
+            time: now_ms() - t00,
+            population_size: self.population.len(),
+            weights,
+            average_scores: avg_scores,
+            max_scores,
+            neighborhood_grading_time: time_grading,
+            mosa_time: time_mosa,
+            graded: graded_num,
+            temperature: self.strategy.parameter(),
+            history_size: self.history.len(),
+            novel: novel_num,
+            revisited,
+            graded_novelty,
+            archive_size: self.archive.len(),
+            hypervolume,
+            igd,
+            spread,
+            neighborhood_average: average_scores,
+        };
 
-        let mut average_scores = neighborhoods_scores.iter().enumerate().map(|(i, s)| s / neighborhood_sizes[i]).collect::<Vec<_>>();
-        avg_inplace(&mut average_scores);
+        Iteration {
+            step,
+            front,
+            improvements,
+        }
+    }
+}
 
-        // Penalty
-        
-        let mut factored_scores = average_scores.iter().enumerate().map(|(i, s)| {
-            if *s > avg && last_weights[i] < penalty_thres {
-                (1.0 - decay_constant) * (*s) + complementary_factor
-            } else {
-                *s
-            }
-        }).collect::<Vec<_>>();
+/// What an island reports of an iteration
+struct Iteration {
+    step: crate::log::Step,
+    /// Scores of the new population
+    front: Vec<Vec<f32>>,
+    /// Scores of the solutions which entered the archive
+    improvements: Vec<Vec<f32>>,
+}
 
-        last_weights = factored_scores.clone();
-        avg_inplace(&mut factored_scores);
+/// Optimize starting from a population of feasible solutions, reporting to `observer`
+/// which may stop the run. With several `islands` in config.json, populations evolve
+/// in parallel and regularly send elite solutions to the next island.
+///
+/// Panics if `initial` is empty.
+pub fn optimize_solution(
+    initial: Vec<TIMEMAP>,
+    project: &Project,
+    observer: &mut dyn Observer,
+) -> Vec<TIMEMAP> {
+    assert!(
+        !initial.is_empty(),
+        "The optimization starts from at least one initial solution"
+    );
+    let num_islands = project.config.islands.max(1);
+
+    // Initial solutions are dealt to the islands, each gets at least one
+    let mut populations: Vec<Vec<Solution>> = vec![vec![]; num_islands];
+    for k in 0..initial.len().max(num_islands) {
+        match Solution::from_timemap(&initial[k % initial.len()], project) {
+            Ok(s) => populations[k % num_islands].push(s),
+            Err(e) => fatal!("Invalid initial solution: {e}"),
+        }
+    }
 
-        factored_weights = factored_scores;
-        if i > warmup {
-            // Adjust weights
-            let mut expected_num = 0.0;
-            for (i, w) in factored_weights.iter().enumerate() {
-                expected_num += neighborhood_sizes[i] * *w;
-            }
+    let mut islands: Vec<Island> = populations
+        .into_iter()
+        .enumerate()
+        .map(|(id, population)| Island::new(id, population, project))
+        .collect();
 
-            let factor = expect_graded_num as f32 / expected_num;
-            for w in factored_weights.iter_mut() {
-                *w *= factor;
+    for i in 0..50 {
+        let iterations: Vec<Iteration> = islands
+            .par_iter_mut()
+            .map(|island| island.step(i, project))
+            .collect();
+
+        // Every observer hears of the whole iteration before the run stops
+        let mut stop = false;
+        for it in iterations {
+            for scores in it.improvements.iter() {
+                stop |= observer.on_improvement(it.step.island, scores).is_break();
             }
+            stop |= observer.on_step(&it.step, &it.front).is_break();
+        }
+        if stop {
+            break;
         }
 
+        if num_islands > 1 && (i + 1) % project.config.migration_interval.max(1) == 0 {
+            // Ring: each island sends to the next one
+            let emigrants: Vec<Vec<Graded>> = islands
+                .iter()
+                .map(|island| island.emigrants(project.config.migration_size))
+                .collect();
+            for (k, e) in emigrants.into_iter().enumerate() {
+                islands[(k + 1) % num_islands].immigrate(e);
+            }
+        }
+    }
 
-        crate::log::step(crate::log::Step{
-            i,
-            weights: last_weights.clone(),
-            average_scores: avg_scores.clone(),
-            max_scores: max_scores.clone(),
-            neighborhood_grading_time: time_grading,
-            mosa_time: time_mosa,
-graded: graded_num,
-            temperature: temp,
-            history_size: history.len(),
-neighborhood_average: average_scores.clone(),
-        });
-
-        println!(
-            "{i} in {}ms (NG: {time_grading}, MOSA: {time_mosa}). Avg: {:?}. Max: {:?}.\nS: {:?}. W: {:?} T: {}. G: {}. P: {}",
-            now_ms() - t00,
-            avg_scores,
-            max_scores,
-            average_scores, 
-            last_weights,
-            temp,
-            graded_num,
-            pop_size
-        );
-
-        temp *= 0.998;
+    let mut archive = Archive::with_capacity(project.config.archive_size);
+    let mut population = vec![];
+    for island in islands {
+        archive.update(island.archive.into_members());
+        population.extend(island.population);
     }
 
-    // s.into_inner()
-    population.into_iter().map(|e| e.into_inner()).collect()
+    let solutions: Vec<TIMEMAP> = if archive.is_empty() {
+        population.into_iter().map(|e| e.to_timemap()).collect()
+    } else {
+        // Every non-dominated solution of the run, not only the last population
+        archive
+            .into_members()
+            .into_iter()
+            .map(|g| g.solution.to_timemap())
+            .collect()
+    };
+
+    observer.on_finish(project, &solutions);
+    solutions
 }
 
 mod test {
     use super::*;
 
+    #[test]
+    #[should_panic(expected = "at least one initial solution")]
+    fn test_empty_initial() {
+        let project = Project::parse("./converted/comp01");
+        optimize_solution(vec![], &project, &mut crate::log::JsonLog::default());
+    }
+
     #[test]
     fn test_counter() {
         let proj = Project::parse("./demo");
 
-        let mut dummy_events = vec![];
-
         let test_kind = EventKind(1);
 
-        let test_event = proj
-            .events
-            .events_with_kind(test_kind)
-            .into_iter()
-            .next()
-            .unwrap();
+        let mut test_events = proj.events.events_with_kind(test_kind).into_iter();
 
-        dummy_events.push(vec![(test_event, Room(0))]);
-        dummy_events.push(vec![]);
-        dummy_events.push(vec![]);
-        dummy_events.push(vec![]);
-        dummy_events.push(vec![(test_event, Room(0))]);
-        dummy_events.push(vec![]);
-        dummy_events.push(vec![(test_event, Room(0))]);
+        let mut dummy_events: TIMEMAP = proj.config.iter_slots().map(|_| vec![]).collect();
+        dummy_events[0].push((test_events.next().unwrap(), Room(0)));
+        dummy_events[4].push((test_events.next().unwrap(), Room(0)));
+        dummy_events[6].push((test_events.next().unwrap(), Room(0)));
 
-        for _ in 0..(proj.config.iter_slots().len() - dummy_events.len()) {
-            dummy_events.push(vec![]);
-        }
-
-        let mut sol = Solution {
-            events: dummy_events,
-            counter: vec![],
-        };
-
-        sol.fill_counter(&proj);
+        let mut sol = Solution::from_timemap(&dummy_events, &proj).unwrap();
 
         assert_eq!(sol.same_kind_events(0, test_kind), 3);
 
         sol.events_of_day_drain(0, &proj);
 
+        assert_eq!(sol.same_kind_events(0, test_kind), 0);
         assert_eq!(sol.events_of_day_drain(0, &proj).len(), 0);
     }
+
+    #[test]
+    fn test_indexes() {
+        let project = Project::parse("./converted/comp01");
+        let timemap = crate::initial::find_initial_solution(&project, false).unwrap();
+        let mut sol = Solution::from_timemap(&timemap, &project).unwrap();
+        let hash = sol.hash();
+
+        let (t, e, r) = sol.iter_all()[0];
+        let p = *project.events.event_attendees(e).iter().next().unwrap();
+        assert!(sol.is_busy(p, t));
+        assert!(sol.event_can_not_fit_in(&e, &r, t, &project));
+
+        assert_eq!(sol.remove(e, &project), Some((t, r)));
+        assert_eq!(sol.remove(e, &project), None);
+        assert!(!sol.is_busy(p, t));
+        assert_eq!(sol.event_in(t, r), None);
+
+        // Indexes and hash match those of the same timetable built from scratch
+        let attendees = project.events.event_attendees(e);
+        let (t2, r2) = project
+            .config
+            .iter_slots()
+            .filter(|t2| attendees.iter().all(|p| !sol.is_busy(*p, *t2)))
+            .flat_map(|t2| project.rooms.iter_all().map(move |r| (t2, r)))
+            .find(|(t2, r2)| (*t2, *r2) != (t, r) && sol.event_in(*t2, *r2).is_none())
+            .unwrap();
+        sol.insert(t2, e, r2, &project);
+        let rebuilt = Solution::from_timemap(&sol.to_timemap(), &project).unwrap();
+        assert_eq!(sol.hash(), history::fingerprint(&sol.to_timemap()));
+        assert_eq!(sol.busy, rebuilt.busy);
+        assert_eq!(sol.counter, rebuilt.counter);
+        assert!(sol == rebuilt);
+
+        sol.remove(e, &project);
+        sol.insert(t, e, r, &project);
+        assert_eq!(sol.hash(), hash);
+        assert_eq!(sol.to_timemap().concat().len(), timemap.concat().len());
+
+        let slot = sol.events_in_slot_drain(t, &project);
+        sol.set_slot(t, slot, &project);
+        assert_eq!(sol.hash(), hash);
+
+        // Room clashes and duplicates are rejected
+        let mut clash = timemap.clone();
+        clash[t2].push((e, r));
+        let violations = Solution::from_timemap(&clash, &project).unwrap_err();
+        assert!(violations
+            .iter()
+            .any(|v| matches!(v, Violation::Duplicate { .. })));
+    }
+
+    #[test]
+    fn test_people_clash() {
+        let project = Project::parse("./converted/comp01");
+        let p = crate::project::Person(0);
+        let mut events = project.people.events_attended_by(p).iter().copied();
+        let (e1, e2) = (events.next().unwrap(), events.next().unwrap());
+
+        // Two events of `p` at once can not be imported
+        let mut timemap: TIMEMAP = project.config.iter_slots().map(|_| vec![]).collect();
+        timemap[0] = vec![(e1, Room(0)), (e2, Room(1))];
+        let violations = Solution::from_timemap(&timemap, &project).unwrap_err();
+        assert!(violations
+            .iter()
+            .any(|v| matches!(v, Violation::PeopleClash { .. })));
+
+        // Once one of them is removed, `p` is still busy with the other
+        timemap[1] = vec![(e2, Room(0))];
+        timemap[0].pop();
+        let mut sol = Solution::from_timemap(&timemap, &project).unwrap();
+        sol.remove(e2, &project);
+        assert!(sol.is_busy(p, 0));
+        assert!(!sol.is_busy(p, 1));
+    }
 }
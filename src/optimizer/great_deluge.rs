@@ -0,0 +1,111 @@
+use rand::seq::IteratorRandom;
+
+use crate::{
+    optimize::Graded,
+    pareto::{Archive, CanDominate},
+};
+
+use super::Strategy;
+
+/// Great deluge: moves to a random neighbor above the water level, which starts at
+/// the first solution and rises each iteration by `rate` of its score magnitude
+pub struct GreatDeluge {
+    current: Option<Graded>,
+    level: f32,
+    rate: f32,
+    /// Rise of the level per iteration
+    rain: f32,
+}
+
+impl GreatDeluge {
+    pub fn new(rate: f32) -> Self {
+        GreatDeluge {
+            current: None,
+            level: f32::MIN,
+            rate,
+            rain: 0.0,
+        }
+    }
+}
+
+impl Strategy for GreatDeluge {
+    fn select(&mut self, neighbors: Vec<Graded>, _archive: &Archive<Graded>) -> Vec<Graded> {
+        let next = match self.current.take() {
+            None => {
+                let first = super::best(neighbors);
+                if let Some(f) = first.as_ref() {
+                    self.level = f.sum();
+                    // A level of 0 would never rise
+                    let magnitude = if self.level == 0.0 {
+                        1.0
+                    } else {
+                        self.level.abs()
+                    };
+                    self.rain = self.rate * magnitude;
+                }
+                first
+            }
+            Some(current) => neighbors
+                .into_iter()
+                .filter(|n| n.sum() >= self.level)
+                .choose(&mut rand::thread_rng())
+                .or(Some(current)),
+        };
+
+        let Some(next) = next else { return vec![] };
+
+        self.level += self.rain;
+        self.current = Some(next.clone());
+        vec![next]
+    }
+
+    fn parameter(&self) -> f32 {
+        self.level
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::optimize::Solution;
+
+    #[test]
+    fn test_select() {
+        let project = crate::project::Project::parse("./converted/comp01");
+        let archive = Archive::with_capacity(1);
+        // Sums of the scores of the next population
+        let select = |s: &mut GreatDeluge, sums: &[f32]| -> Vec<f32> {
+            let empty = Solution::empty(&project);
+            let neighbors = sums
+                .iter()
+                .map(|sum| Graded::with_scores(vec![*sum], empty.clone()))
+                .collect();
+            s.select(neighbors, &archive)
+                .iter()
+                .map(|g| g.sum())
+                .collect()
+        };
+
+        let mut deluge = GreatDeluge::new(0.1);
+        assert!(select(&mut deluge, &[]).is_empty());
+        assert_eq!(select(&mut deluge, &[-10.0]), vec![-10.0]);
+        assert_eq!(deluge.parameter(), -9.0);
+
+        // Below the level
+        assert_eq!(select(&mut deluge, &[-9.5]), vec![-10.0]);
+        assert_eq!(deluge.parameter(), -8.0);
+        assert_eq!(select(&mut deluge, &[-7.0]), vec![-7.0]);
+        // Without neighbors
+        assert_eq!(select(&mut deluge, &[]), vec![-7.0]);
+
+        // The level rises by the same amount, even through 0
+        for _ in 0..10 {
+            select(&mut deluge, &[]);
+        }
+        assert!((deluge.parameter() - 4.0).abs() < 1e-4);
+
+        let mut deluge = GreatDeluge::new(0.1);
+        select(&mut deluge, &[0.0]);
+        assert!(deluge.parameter() > 0.0);
+    }
+}
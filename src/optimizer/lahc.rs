@@ -0,0 +1,102 @@
+use rand::seq::IteratorRandom;
+
+use crate::{
+    optimize::Graded,
+    pareto::{Archive, CanDominate},
+};
+
+use super::Strategy;
+
+/// Late acceptance hill climbing: moves to a random neighbor which is not worse than
+/// the current solution, or than the current solution `length` iterations ago
+pub struct LateAcceptance {
+    current: Option<Graded>,
+    /// Sum of scores of the last `length` current solutions
+    history: Vec<f32>,
+    length: usize,
+    i: usize,
+}
+
+impl LateAcceptance {
+    pub fn new(length: usize) -> Self {
+        LateAcceptance {
+            current: None,
+            history: vec![],
+            length: length.max(1),
+            i: 0,
+        }
+    }
+}
+
+impl Strategy for LateAcceptance {
+    fn select(&mut self, neighbors: Vec<Graded>, _archive: &Archive<Graded>) -> Vec<Graded> {
+        let next = match self.current.take() {
+            None => super::best(neighbors),
+            Some(current) => {
+                let late = self.history[self.i % self.history.len()];
+                let bound = late.min(current.sum());
+
+                neighbors
+                    .into_iter()
+                    .filter(|n| n.sum() >= bound)
+                    .choose(&mut rand::thread_rng())
+                    .or(Some(current))
+            }
+        };
+
+        let Some(next) = next else { return vec![] };
+
+        if self.history.len() < self.length {
+            self.history.push(next.sum());
+        } else {
+            self.history[self.i % self.length] = next.sum();
+        }
+        self.i += 1;
+
+        self.current = Some(next.clone());
+        vec![next]
+    }
+
+    fn parameter(&self) -> f32 {
+        match self.history.len() {
+            0 => 0.0,
+            len => self.history[self.i % len],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::optimize::Solution;
+
+    #[test]
+    fn test_select() {
+        let project = crate::project::Project::parse("./converted/comp01");
+        let archive = Archive::with_capacity(1);
+        // Sums of the scores of the next population
+        let select = |s: &mut LateAcceptance, sums: &[f32]| -> Vec<f32> {
+            let empty = Solution::empty(&project);
+            let neighbors = sums
+                .iter()
+                .map(|sum| Graded::with_scores(vec![*sum], empty.clone()))
+                .collect();
+            s.select(neighbors, &archive)
+                .iter()
+                .map(|g| g.sum())
+                .collect()
+        };
+
+        let mut lahc = LateAcceptance::new(2);
+        assert!(select(&mut lahc, &[]).is_empty());
+        assert_eq!(select(&mut lahc, &[-5.0]), vec![-5.0]);
+        assert_eq!(select(&mut lahc, &[-3.0]), vec![-3.0]);
+
+        // Worse than the current solution, not than the one 2 iterations ago
+        assert_eq!(select(&mut lahc, &[-4.0]), vec![-4.0]);
+        // Worse than both
+        assert_eq!(select(&mut lahc, &[-4.5]), vec![-4.0]);
+        // Without neighbors
+        assert_eq!(select(&mut lahc, &[]), vec![-4.0]);
+    }
+}
@@ -0,0 +1,55 @@
+// Strategies of the optimization phase.
+//
+// `optimize_solution` generates and grades the neighbors of the current population
+// with the project neighborhoods and criteria; a strategy then picks the next population.
+// MOSA keeps a population, the others follow a single solution and compare
+// neighbors by the sum of their (normalised) scores.
+
+mod great_deluge;
+mod lahc;
+mod mosa;
+mod tabu;
+
+pub use great_deluge::GreatDeluge;
+pub use lahc::LateAcceptance;
+pub use mosa::Mosa;
+pub use tabu::Tabu;
+
+use crate::{fatal, optimize::Graded, pareto::Archive, project::Project};
+
+pub trait Strategy: Send {
+    /// Next population among the graded neighbors of the current one. Strategies
+    /// following a single solution keep it when no neighbor is acceptable; when
+    /// nothing is selected the island keeps its population.
+    fn select(&mut self, neighbors: Vec<Graded>, archive: &Archive<Graded>) -> Vec<Graded>;
+
+    /// Control parameter logged each iteration: temperature, water level...
+    fn parameter(&self) -> f32;
+}
+
+/// Strategy named by `optimizer` in config.json: "mosa", "tabu", "lahc" or "great_deluge".
+/// Each island anneals at `island_temperature_factor` times the temperature of the previous one.
+pub fn from_config(project: &Project, island: usize) -> Box<dyn Strategy> {
+    let config = &project.config;
+
+    match config.optimizer.as_str() {
+        "mosa" => Box::new(Mosa::new(
+            config.selection.clone(),
+            config.population_size,
+            config.initial_temperature * config.island_temperature_factor.powi(island as i32),
+        )),
+        "tabu" => Box::new(Tabu::new(config.optimizer_tabu_size)),
+        "lahc" => Box::new(LateAcceptance::new(config.lahc_length)),
+        "great_deluge" => Box::new(GreatDeluge::new(config.deluge_rate)),
+        _ => fatal!("Invalid optimizer: {}", config.optimizer),
+    }
+}
+
+/// The neighbor with the largest sum of scores
+fn best(neighbors: Vec<Graded>) -> Option<Graded> {
+    use crate::pareto::CanDominate;
+
+    neighbors
+        .into_iter()
+        .max_by(|a, b| a.sum().partial_cmp(&b.sum()).unwrap())
+}
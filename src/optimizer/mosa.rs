@@ -0,0 +1,45 @@
+use crate::{fatal, optimize::Graded, pareto, pareto::Archive};
+
+use super::Strategy;
+
+/// Multi-objective simulated annealing over a population
+pub struct Mosa {
+    /// "mosa", "nsga2" or "weighted", see `Config::selection`
+    selection: String,
+    population_size: usize,
+    temp: f32,
+}
+
+impl Mosa {
+    pub fn new(selection: String, population_size: usize, temp: f32) -> Self {
+        Mosa {
+            selection,
+            population_size,
+            temp,
+        }
+    }
+}
+
+impl Strategy for Mosa {
+    fn select(&mut self, neighbors: Vec<Graded>, archive: &Archive<Graded>) -> Vec<Graded> {
+        let selected = match self.selection.as_str() {
+            "mosa" => pareto::random_mosa(neighbors, self.population_size, self.temp),
+            "nsga2" => {
+                // Elitism: archived solutions compete with the neighbors
+                let mut candidates = neighbors;
+                candidates.extend(archive.members().iter().cloned());
+
+                pareto::nsga2(candidates, self.population_size)
+            }
+            "weighted" => pareto::best_by_sum(neighbors, self.population_size),
+            _ => fatal!("Invalid selection: {}", self.selection),
+        };
+
+        self.temp *= 0.998;
+        selected
+    }
+
+    fn parameter(&self) -> f32 {
+        self.temp
+    }
+}
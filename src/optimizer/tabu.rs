@@ -0,0 +1,129 @@
+use std::collections::VecDeque;
+
+use crate::{
+    optimize::{Graded, Solution},
+    pareto::{Archive, CanDominate},
+    project::Event,
+};
+
+use super::Strategy;
+
+/// Tabu search on moves: an event may not return to a slot it recently left,
+/// unless this gives the best solution so far
+pub struct Tabu {
+    current: Option<Graded>,
+    best_sum: f32,
+    /// (event, slot) recently left
+    tabu: VecDeque<(Event, usize)>,
+    tenure: usize,
+}
+
+impl Tabu {
+    pub fn new(tenure: usize) -> Self {
+        Tabu {
+            current: None,
+            best_sum: f32::MIN,
+            tabu: VecDeque::new(),
+            tenure,
+        }
+    }
+
+    fn is_tabu(&self, moves: &[(Event, usize, usize)]) -> bool {
+        moves
+            .iter()
+            .any(|(e, _, to)| self.tabu.contains(&(*e, *to)))
+    }
+}
+
+/// Events scheduled at another slot in `to`: (event, slot in `from`, slot in `to`)
+fn moves(from: &Solution, to: &Solution) -> Vec<(Event, usize, usize)> {
+    let from = from.iter_all();
+    let mut slots = vec![usize::MAX; from.iter().map(|(_, e, _)| e.0 + 1).max().unwrap_or(0)];
+    for (t, e, _) in from {
+        slots[e.0] = t;
+    }
+
+    to.iter_all()
+        .into_iter()
+        .filter_map(|(t, e, _)| match slots.get(e.0) {
+            Some(old) if *old != t => Some((e, *old, t)),
+            _ => None,
+        })
+        .collect()
+}
+
+impl Strategy for Tabu {
+    fn select(&mut self, neighbors: Vec<Graded>, _archive: &Archive<Graded>) -> Vec<Graded> {
+        let next = match self.current.take() {
+            None => super::best(neighbors),
+            Some(current) => {
+                let candidates = neighbors
+                    .into_iter()
+                    .map(|n| (moves(&current.solution, &n.solution), n))
+                    .filter(|(m, n)| !self.is_tabu(m) || n.sum() > self.best_sum)
+                    .collect::<Vec<_>>();
+
+                let best = candidates
+                    .into_iter()
+                    .max_by(|a, b| a.1.sum().partial_cmp(&b.1.sum()).unwrap());
+
+                match best {
+                    Some((m, n)) => {
+                        for (e, from, _) in m {
+                            self.tabu.push_back((e, from));
+                        }
+                        while self.tabu.len() > self.tenure {
+                            self.tabu.pop_front();
+                        }
+                        Some(n)
+                    }
+                    // Every move is tabu: stay
+                    None => Some(current),
+                }
+            }
+        };
+
+        let Some(next) = next else { return vec![] };
+        self.best_sum = self.best_sum.max(next.sum());
+        self.current = Some(next.clone());
+        vec![next]
+    }
+
+    fn parameter(&self) -> f32 {
+        self.tabu.len() as f32
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{optimize::TIMEMAP, project::Room};
+
+    #[test]
+    fn test_moves() {
+        let project = crate::project::Project::parse("./converted/comp01");
+        let timemap = |slots: Vec<Vec<(Event, Room)>>| {
+            let mut events: TIMEMAP = project.config.iter_slots().map(|_| vec![]).collect();
+            for (t, v) in slots.into_iter().enumerate() {
+                events[t] = v;
+            }
+            Solution::from_timemap(&events, &project).unwrap()
+        };
+        let from = timemap(vec![
+            vec![(Event(0), Room(0)), (Event(2), Room(1))],
+            vec![(Event(1), Room(0))],
+        ]);
+        let to = timemap(vec![
+            vec![(Event(2), Room(0))],
+            vec![(Event(1), Room(0)), (Event(0), Room(1))],
+        ]);
+
+        // Room changes are not moves
+        assert_eq!(moves(&from, &to), vec![(Event(0), 0, 1)]);
+
+        let mut tabu = Tabu::new(10);
+        tabu.tabu.push_back((Event(0), 1));
+        assert!(tabu.is_tabu(&moves(&from, &to)));
+        assert!(!tabu.is_tabu(&moves(&to, &from)));
+    }
+}
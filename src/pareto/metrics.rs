@@ -0,0 +1,214 @@
+// Quality indicators of a Pareto front.
+// Every objective is maximized, as everywhere else in the optimizer.
+
+fn distance(a: &[f32], b: &[f32]) -> f32 {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| (x - y).powi(2))
+        .sum::<f32>()
+        .sqrt()
+}
+
+fn min_distance(p: &[f32], front: &[Vec<f32>]) -> f32 {
+    front
+        .iter()
+        .map(|q| distance(p, q))
+        .fold(f32::INFINITY, f32::min)
+}
+
+/// Up to this many objectives the hypervolume is exact, above it is estimated
+const EXACT_HYPERVOLUME_MAX_OBJECTIVES: usize = 4;
+/// Above this many objectives even the estimate is too coarse to be worth it
+const HYPERVOLUME_MAX_OBJECTIVES: usize = 8;
+const HYPERVOLUME_SAMPLES: usize = 10_000;
+
+/// Volume of the objective space dominated by the front and bounded by `reference`.
+/// Points which do not dominate the reference are ignored.
+/// None above `HYPERVOLUME_MAX_OBJECTIVES` or when the volume does not fit a f64.
+pub fn hypervolume(front: &[Vec<f32>], reference: &[f32]) -> Option<f64> {
+    if reference.len() > HYPERVOLUME_MAX_OBJECTIVES {
+        return None;
+    }
+
+    let points: Vec<Vec<f64>> = front
+        .iter()
+        .filter(|p| p.iter().zip(reference).all(|(v, r)| v > r))
+        .map(|p| p.iter().map(|v| *v as f64).collect())
+        .collect();
+    let reference: Vec<f64> = reference.iter().map(|r| *r as f64).collect();
+
+    let volume = if reference.len() <= EXACT_HYPERVOLUME_MAX_OBJECTIVES {
+        slice_volume(points, &reference)
+    } else {
+        sampled_volume(&points, &reference, HYPERVOLUME_SAMPLES)
+    };
+
+    volume.is_finite().then_some(volume)
+}
+
+/// Hypervolume by slicing along the last objective
+fn slice_volume(mut points: Vec<Vec<f64>>, reference: &[f64]) -> f64 {
+    let m = reference.len();
+    if points.is_empty() {
+        return 0.0;
+    }
+    if m == 1 {
+        return points.iter().map(|p| p[0]).fold(f64::MIN, f64::max) - reference[0];
+    }
+
+    // Best last objective first: a slice contains every point at least as good
+    points.sort_by(|a, b| b[m - 1].partial_cmp(&a[m - 1]).unwrap());
+
+    let mut volume = 0.0;
+    for i in 0..points.len() {
+        let lower = points
+            .get(i + 1)
+            .map(|p| p[m - 1])
+            .unwrap_or(reference[m - 1]);
+        let height = points[i][m - 1] - lower;
+        if height <= 0.0 {
+            continue;
+        }
+
+        let slice: Vec<Vec<f64>> = points[..=i].iter().map(|p| p[..m - 1].to_vec()).collect();
+        volume += height * slice_volume(slice, &reference[..m - 1]);
+    }
+
+    volume
+}
+
+/// Monte Carlo estimate of the hypervolume, sampling the box between the reference
+/// and the best value of each objective
+fn sampled_volume(points: &[Vec<f64>], reference: &[f64], samples: usize) -> f64 {
+    use rand::Rng;
+
+    if points.is_empty() {
+        return 0.0;
+    }
+
+    let ideal: Vec<f64> = (0..reference.len())
+        .map(|k| points.iter().map(|p| p[k]).fold(f64::MIN, f64::max))
+        .collect();
+    let volume: f64 = ideal.iter().zip(reference).map(|(i, r)| i - r).product();
+
+    let mut rng = rand::thread_rng();
+    let mut sample = vec![0.0; reference.len()];
+    let mut hits = 0;
+    for _ in 0..samples {
+        for (k, v) in sample.iter_mut().enumerate() {
+            *v = rng.gen_range(reference[k]..ideal[k]);
+        }
+        if points
+            .iter()
+            .any(|p| p.iter().zip(sample.iter()).all(|(a, b)| a >= b))
+        {
+            hits += 1;
+        }
+    }
+
+    volume * hits as f64 / samples as f64
+}
+
+/// Mean distance from each point of the front to the closest point of the reference front
+pub fn generational_distance(front: &[Vec<f32>], reference: &[Vec<f32>]) -> f32 {
+    if front.is_empty() || reference.is_empty() {
+        return f32::INFINITY;
+    }
+
+    front
+        .iter()
+        .map(|p| min_distance(p, reference))
+        .sum::<f32>()
+        / front.len() as f32
+}
+
+/// Mean distance from each point of the reference front to the closest point of the front
+pub fn inverted_generational_distance(front: &[Vec<f32>], reference: &[Vec<f32>]) -> f32 {
+    generational_distance(reference, front)
+}
+
+/// Uniformity of the front: mean absolute deviation of the distances to the nearest
+/// neighbor, relative to their mean. 0 when all points are evenly spaced.
+pub fn spread(front: &[Vec<f32>]) -> f32 {
+    if front.len() < 2 {
+        return 0.0;
+    }
+
+    let nearest: Vec<f32> = front
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            front
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, q)| distance(p, q))
+                .fold(f32::INFINITY, f32::min)
+        })
+        .collect();
+
+    let mean = nearest.iter().sum::<f32>() / nearest.len() as f32;
+    if mean == 0.0 {
+        return 0.0;
+    }
+
+    nearest.iter().map(|d| (d - mean).abs()).sum::<f32>() / (nearest.len() as f32 * mean)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hypervolume() {
+        let front = vec![vec![1.0, 3.0], vec![2.0, 2.0], vec![3.0, 1.0]];
+        assert_eq!(hypervolume(&front, &[0.0, 0.0]), Some(6.0));
+
+        // Dominated and out of bound points do not count
+        let front = vec![vec![2.0, 2.0], vec![1.0, 1.0], vec![-1.0, 5.0]];
+        assert_eq!(hypervolume(&front, &[0.0, 0.0]), Some(4.0));
+
+        let cube = vec![vec![1.0, 2.0, 3.0]];
+        assert_eq!(hypervolume(&cube, &[0.0, 0.0, 0.0]), Some(6.0));
+
+        // Estimated above 4 objectives
+        let front = vec![vec![1.0, 1.0, 1.0, 1.0, 1.0], vec![0.5, 1.0, 1.0, 1.0, 1.0]];
+        assert_eq!(hypervolume(&front, &[0.0; 5]), Some(1.0));
+        let front = vec![vec![1.0, 0.5, 1.0, 1.0, 1.0], vec![0.5, 1.0, 1.0, 1.0, 1.0]];
+        let hv = hypervolume(&front, &[0.0; 5]).unwrap();
+        assert!((hv - 0.75).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_hypervolume_overflow() {
+        // Ranges of 6e38 over 8 objectives are beyond f64
+        let front = vec![vec![f32::MAX; 8]];
+        assert_eq!(hypervolume(&front, &[f32::MIN; 8]), None);
+
+        // Not even estimated above 8 objectives
+        let front = vec![vec![1.0; 9]];
+        assert_eq!(hypervolume(&front, &[0.0; 9]), None);
+    }
+
+    #[test]
+    fn test_distances() {
+        let reference = vec![vec![0.0, 2.0], vec![2.0, 0.0]];
+        let front = vec![vec![0.0, 1.0]];
+
+        assert_eq!(generational_distance(&front, &reference), 1.0);
+        assert_eq!(
+            inverted_generational_distance(&front, &reference),
+            (1.0 + 5f32.sqrt()) / 2.0
+        );
+        assert_eq!(inverted_generational_distance(&reference, &reference), 0.0);
+    }
+
+    #[test]
+    fn test_spread() {
+        let even = vec![vec![0.0, 2.0], vec![1.0, 1.0], vec![2.0, 0.0]];
+        assert_eq!(spread(&even), 0.0);
+
+        let uneven = vec![vec![0.0, 3.0], vec![0.1, 2.9], vec![3.0, 0.0]];
+        assert!(spread(&uneven) > 0.5);
+    }
+}
@@ -0,0 +1,952 @@
+pub mod metrics;
+
+use std::cmp::Ordering;
+
+use rand::seq::SliceRandom;
+
+pub trait CanDominate {
+    fn dominates(&self, other: &Self) -> bool;
+    /// Order in which no solution dominates a later one, e.g. the function `compare_lexicographic`
+    fn compare_lexicographic(&self, other: &Self) -> Ordering;
+    fn sum(&self) -> f32 {
+        -1.0
+    }
+    /// Objective values, used for diversity measures
+    fn objectives(&self) -> &[f32] {
+        &[]
+    }
+    /// Values along which a dominating solution is never worse, by default the
+    /// objectives. `best_order_filter` sorts the solutions by each of them
+    fn sort_keys(&self) -> Vec<f32> {
+        self.objectives().to_vec()
+    }
+}
+
+/// Pareto dominance, every objective maximized: `a` is at least as good
+/// as `b` on all objectives and better on at least one
+pub fn dominates(a: &[f32], b: &[f32]) -> bool {
+    let mut any_better = false;
+
+    for (x, y) in a.iter().zip(b) {
+        if x < y {
+            return false;
+        }
+        if x > y {
+            any_better = true;
+        }
+    }
+
+    any_better
+}
+
+/// Box of a value in a grid of size `epsilon`, 0 disables the grid
+fn epsilon_box(v: f32, epsilon: f32) -> f32 {
+    if epsilon > 0.0 {
+        (v / epsilon).floor()
+    } else {
+        v
+    }
+}
+
+/// Boxes of the values in the grids of `epsilon_dominates`
+pub fn boxes(a: &[f32], epsilon: &[f32]) -> Vec<f32> {
+    a.iter()
+        .zip(epsilon.iter().chain(std::iter::repeat(&0.0)))
+        .map(|(v, e)| epsilon_box(*v, *e))
+        .collect()
+}
+
+/// ε-box dominance: objectives are compared in boxes of size `epsilon[i]`, and only
+/// within the same box by their values. Differences smaller than ε do not
+/// make a solution better, which keeps the front sparse.
+/// With all epsilons 0, this is the Pareto dominance.
+pub fn epsilon_dominates(a: &[f32], b: &[f32], epsilon: &[f32]) -> bool {
+    if epsilon.iter().all(|e| *e == 0.0) {
+        return dominates(a, b);
+    }
+
+    let (box_a, box_b) = (boxes(a, epsilon), boxes(b, epsilon));
+    if box_a == box_b {
+        dominates(a, b)
+    } else {
+        dominates(&box_a, &box_b)
+    }
+}
+
+/// Lexicographic order of objectives, a solution never dominates a smaller one
+pub fn compare_lexicographic(a: &[f32], b: &[f32]) -> Ordering {
+    for (x, y) in a.iter().zip(b) {
+        match x.partial_cmp(y).unwrap() {
+            Ordering::Equal => continue,
+            o => return o,
+        }
+    }
+    Ordering::Equal
+}
+
+/// Order compatible with `epsilon_dominates`: by boxes, then by values
+pub fn compare_epsilon(a: &[f32], b: &[f32], epsilon: &[f32]) -> Ordering {
+    if epsilon.iter().all(|e| *e == 0.0) {
+        return compare_lexicographic(a, b);
+    }
+
+    compare_lexicographic(&boxes(a, epsilon), &boxes(b, epsilon))
+        .then_with(|| compare_lexicographic(a, b))
+}
+
+pub fn naive<T: CanDominate>(data: Vec<T>) -> Vec<T> {
+    let mut pareto_set: Vec<T> = Vec::new();
+
+    for sol in data {
+        let mut flag = true;
+
+        let mut new_pareto_set = vec![];
+        for p in pareto_set {
+            if p.dominates(&sol) {
+                flag = false;
+            }
+            if !sol.dominates(&p) {
+                new_pareto_set.push(p);
+            }
+        }
+
+        pareto_set = new_pareto_set;
+        if flag {
+            pareto_set.push(sol);
+        }
+    }
+
+    pareto_set
+}
+
+/// Kung Algorithm
+pub fn kung_recursive<T: CanDominate>(mut data: Vec<T>) -> Vec<T> {
+    if data.len() == 1 {
+        return data;
+    }
+
+    // Sort so that the left part can not dominate the right one
+    data.sort_by(|a, b| a.compare_lexicographic(b));
+
+    // Split the data into two parts
+    // Avoid clone
+    let right = data.split_off(data.len() / 2);
+    let left = data;
+
+    // Recursively find the skyline of each part
+    let left_skyline = kung_recursive(left);
+    let right_skyline = kung_recursive(right);
+
+    // Merge the two skylines
+    let mut skyline = Vec::new();
+
+    for l in left_skyline {
+        let mut flag = true;
+
+        for r in &right_skyline {
+            if r.dominates(&l) {
+                flag = false;
+                break;
+            }
+        }
+        if flag {
+            skyline.push(l);
+        }
+    }
+
+    for r in right_skyline {
+        skyline.push(r);
+    }
+
+    skyline
+}
+
+pub fn kung_recursive_mosa<T: CanDominate + std::fmt::Debug>(
+    mut data: Vec<T>,
+    temp: f32,
+) -> Vec<T> {
+    if data.len() == 1 {
+        return data;
+    }
+
+    // Sort so that the left part can not dominate the right one
+    data.sort_by(|a, b| a.compare_lexicographic(b));
+
+    // Split the data into two parts
+    // Avoid clone
+    let right = data.split_off(data.len() / 2);
+    let left = data;
+
+    // Recursively find the skyline of each part
+    let left_skyline = kung_recursive_mosa(left, temp);
+    let right_skyline = kung_recursive_mosa(right, temp);
+
+    // Merge the two skylines
+    let mut skyline = Vec::new();
+
+    for l in left_skyline {
+        let mut energy = 0.0; // Lower is better
+        let sum = l.sum();
+
+        for r in &right_skyline {
+            if r.dominates(&l) {
+                energy += r.sum() - sum;
+            }
+        }
+        let rand: f32 = rand::random();
+
+        if energy == 0.0 {
+            skyline.push(l);
+            continue;
+        }
+
+        let prob = 1.0 / (1.0 + (energy / temp).exp());
+
+        if rand <= prob {
+            skyline.push(l);
+        }
+    }
+
+    for r in right_skyline {
+        skyline.push(r);
+    }
+
+    skyline
+}
+
+/// Sort-Filter-Skyline: once sorted so that no solution dominates an earlier one,
+/// a solution is non-dominated iff no previously kept one dominates it.
+/// O(n log n + n·f·m) for a front of size f, without recursion.
+pub fn sort_filter<T: CanDominate>(mut data: Vec<T>) -> Vec<T> {
+    data.sort_by(|a, b| b.compare_lexicographic(a));
+
+    let mut skyline: Vec<T> = Vec::new();
+    for d in data {
+        if !skyline.iter().any(|s| s.dominates(&d)) {
+            skyline.push(d);
+        }
+    }
+
+    skyline
+}
+
+/// `sort_filter` where dominated solutions are still kept with the MOSA probability,
+/// from their energy: how much better the kept solutions dominating them are
+pub fn sort_filter_mosa<T: CanDominate>(mut data: Vec<T>, temp: f32) -> Vec<T> {
+    data.sort_by(|a, b| b.compare_lexicographic(a));
+
+    let mut skyline: Vec<T> = Vec::new();
+    for d in data {
+        let sum = d.sum();
+        let energy: f32 = skyline
+            .iter()
+            .filter(|s| s.dominates(&d))
+            .map(|s| s.sum() - sum)
+            .sum();
+
+        if energy == 0.0 || rand::random::<f32>() <= 1.0 / (1.0 + (energy / temp).exp()) {
+            skyline.push(d);
+        }
+    }
+
+    skyline
+}
+
+/// Best Order Sort (Roy et al., 2016) restricted to the first front.
+/// Solutions are sorted by each objective and visited column by column: a solution is
+/// only compared, when first met, with the kept ones before it in that column.
+/// Falls back to `sort_filter` for solutions without `sort_keys`.
+/// Sorting every column costs more than `sort_filter` saves on the small fronts of
+/// graded neighbors (see `benches/pareto.rs`), so the optimizer keeps `sort_filter`.
+pub fn best_order_filter<T: CanDominate>(data: Vec<T>) -> Vec<T> {
+    best_order(data, sort_filter, |data, before, d| {
+        !before.iter().any(|s| data[*s].dominates(&data[d]))
+    })
+}
+
+/// `best_order_filter` where dominated solutions are still kept with the MOSA probability,
+/// as in `sort_filter_mosa`
+pub fn best_order_filter_mosa<T: CanDominate>(data: Vec<T>, temp: f32) -> Vec<T> {
+    best_order(
+        data,
+        |data| sort_filter_mosa(data, temp),
+        |data, before, d| {
+            let sum = data[d].sum();
+            let energy: f32 = before
+                .iter()
+                .filter(|s| data[**s].dominates(&data[d]))
+                .map(|s| data[*s].sum() - sum)
+                .sum();
+
+            energy == 0.0 || rand::random::<f32>() <= 1.0 / (1.0 + (energy / temp).exp())
+        },
+    )
+}
+
+/// Visits the solutions in best order, `keep` decides on each from the kept solutions
+/// before it in the column where it is first met. Every solution dominating it is there.
+fn best_order<T: CanDominate>(
+    data: Vec<T>,
+    fallback: impl FnOnce(Vec<T>) -> Vec<T>,
+    mut keep: impl FnMut(&[T], &[usize], usize) -> bool,
+) -> Vec<T> {
+    let n = data.len();
+    let keys: Vec<Vec<f32>> = data.iter().map(|d| d.sort_keys()).collect();
+    let m = keys.first().map_or(0, |k| k.len());
+    if m == 0 {
+        return fallback(data);
+    }
+
+    // Best first, ties in the order of `sort_filter` so that dominating solutions come first
+    let columns: Vec<Vec<usize>> = (0..m)
+        .map(|j| {
+            let mut column: Vec<usize> = (0..n).collect();
+            column.sort_by(|a, b| {
+                keys[*b][j]
+                    .partial_cmp(&keys[*a][j])
+                    .unwrap()
+                    .then_with(|| data[*b].compare_lexicographic(&data[*a]))
+            });
+            column
+        })
+        .collect();
+
+    let mut met = vec![false; n];
+    let mut kept = vec![false; n];
+    let mut before: Vec<Vec<usize>> = vec![vec![]; m];
+    let mut remaining = n;
+
+    'rows: for i in 0..n {
+        for (j, column) in columns.iter().enumerate() {
+            let d = column[i];
+            if !met[d] {
+                met[d] = true;
+                remaining -= 1;
+                kept[d] = keep(&data, &before[j], d);
+            }
+            if kept[d] {
+                before[j].push(d);
+            }
+            if remaining == 0 {
+                break 'rows;
+            }
+        }
+    }
+
+    data.into_iter()
+        .zip(kept)
+        .filter_map(|(d, k)| k.then_some(d))
+        .collect()
+}
+
+pub fn random_frontline<T: CanDominate + PartialEq>(mut solutions: Vec<T>, max: usize) -> Vec<T> {
+    // First Dedup
+    solutions.dedup();
+
+    solutions = sort_filter(solutions);
+
+    let mut rng = rand::thread_rng();
+    solutions.shuffle(&mut rng);
+
+    solutions.truncate(max);
+
+    solutions
+}
+
+/// The higher the temp, the higher prob that a inferior solution will be accepted
+/// Temp shall be at same level of sum of criterion
+pub fn random_mosa<T: CanDominate + PartialEq + std::fmt::Debug>(
+    mut solutions: Vec<T>,
+    max: usize,
+    temp: f32,
+) -> Vec<T> {
+    solutions.dedup();
+    solutions = sort_filter_mosa(solutions, temp);
+    let mut rng = rand::thread_rng();
+    solutions.shuffle(&mut rng);
+    solutions.truncate(max);
+    solutions
+}
+
+/// Split into fronts: the first is non-dominated, the second is non-dominated once the first is removed...
+pub fn non_dominated_sort<T: CanDominate>(data: Vec<T>) -> Vec<Vec<T>> {
+    let n = data.len();
+    // Number of solutions dominating i, and solutions dominated by i
+    let mut dominated_by = vec![0; n];
+    let mut dominating = vec![vec![]; n];
+
+    for i in 0..n {
+        for j in (i + 1)..n {
+            if data[i].dominates(&data[j]) {
+                dominating[i].push(j);
+                dominated_by[j] += 1;
+            } else if data[j].dominates(&data[i]) {
+                dominating[j].push(i);
+                dominated_by[i] += 1;
+            }
+        }
+    }
+
+    let mut rank = vec![0; n];
+    let mut current: Vec<usize> = (0..n).filter(|i| dominated_by[*i] == 0).collect();
+    let mut num_fronts = 0;
+
+    while !current.is_empty() {
+        let mut next = vec![];
+        for i in current {
+            rank[i] = num_fronts;
+            for j in dominating[i].iter() {
+                dominated_by[*j] -= 1;
+                if dominated_by[*j] == 0 {
+                    next.push(*j);
+                }
+            }
+        }
+        num_fronts += 1;
+        current = next;
+    }
+
+    let mut fronts: Vec<Vec<T>> = (0..num_fronts).map(|_| vec![]).collect();
+    for (i, d) in data.into_iter().enumerate() {
+        fronts[rank[i]].push(d);
+    }
+    fronts
+}
+
+/// Crowding distance of each solution in a front. Boundary solutions get infinity.
+pub fn crowding_distance<T: CanDominate>(front: &[T]) -> Vec<f32> {
+    let n = front.len();
+    let mut distance = vec![0.0; n];
+    if n == 0 {
+        return distance;
+    }
+
+    for m in 0..front[0].objectives().len() {
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|a, b| {
+            front[*a].objectives()[m]
+                .partial_cmp(&front[*b].objectives()[m])
+                .unwrap()
+        });
+
+        let min = front[order[0]].objectives()[m];
+        let max = front[order[n - 1]].objectives()[m];
+        distance[order[0]] = f32::INFINITY;
+        distance[order[n - 1]] = f32::INFINITY;
+
+        if max == min {
+            continue;
+        }
+
+        for k in 1..(n - 1) {
+            let prev = front[order[k - 1]].objectives()[m];
+            let next = front[order[k + 1]].objectives()[m];
+            distance[order[k]] += (next - prev) / (max - min);
+        }
+    }
+
+    distance
+}
+
+/// NSGA-II selection: whole fronts by rank, the last one by decreasing crowding distance
+pub fn nsga2<T: CanDominate + PartialEq>(mut solutions: Vec<T>, max: usize) -> Vec<T> {
+    solutions.dedup();
+
+    let mut selected = vec![];
+    for front in non_dominated_sort(solutions) {
+        if selected.len() + front.len() <= max {
+            selected.extend(front);
+            continue;
+        }
+
+        let distance = crowding_distance(&front);
+        let mut front: Vec<(f32, T)> = distance.into_iter().zip(front).collect();
+        front.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+
+        let remaining = max - selected.len();
+        selected.extend(front.into_iter().take(remaining).map(|(_, s)| s));
+        break;
+    }
+
+    selected
+}
+
+/// Single objective selection: the solutions with the largest sums
+pub fn best_by_sum<T: CanDominate + PartialEq>(mut solutions: Vec<T>, max: usize) -> Vec<T> {
+    solutions.sort_by(|a, b| b.sum().partial_cmp(&a.sum()).unwrap());
+    solutions.dedup();
+    solutions.truncate(max);
+    solutions
+}
+
+/// Grid divisions per objective used to prune a bounded archive
+const ARCHIVE_GRID_DIVISIONS: usize = 8;
+
+/// Non-dominated solutions found so far, at most `capacity` of them
+#[derive(Debug, Clone)]
+pub struct Archive<T> {
+    members: Vec<T>,
+    capacity: usize,
+}
+
+impl<T> Default for Archive<T> {
+    fn default() -> Self {
+        Archive {
+            members: vec![],
+            capacity: usize::MAX,
+        }
+    }
+}
+
+impl<T> Archive<T> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Archive {
+            members: vec![],
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn members(&self) -> &[T] {
+        &self.members
+    }
+
+    pub fn into_members(self) -> Vec<T> {
+        self.members
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+impl<T: CanDominate + PartialEq> Archive<T> {
+    /// Add candidates, dropping everything dominated
+    pub fn update(&mut self, candidates: Vec<T>) {
+        let mut all = std::mem::take(&mut self.members);
+        for c in candidates {
+            if !all.contains(&c) {
+                all.push(c);
+            }
+        }
+        self.members = sort_filter(all);
+        self.prune();
+    }
+
+    /// Like `update`, but only clones the candidates which are not dominated by the archive.
+    /// Returns the positions of the candidates which entered the archive
+    pub fn update_from<'a, I>(&mut self, candidates: I) -> Vec<usize>
+    where
+        T: Clone + 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        let (positions, candidates): (Vec<usize>, Vec<T>) = candidates
+            .into_iter()
+            .enumerate()
+            .filter(|(_, c)| !self.members.iter().any(|m| m.dominates(c) || m == *c))
+            .map(|(k, c)| (k, c.clone()))
+            .unzip();
+
+        if candidates.is_empty() {
+            return vec![];
+        }
+
+        self.update(candidates.clone());
+        positions
+            .into_iter()
+            .zip(candidates.iter())
+            .filter(|(_, c)| self.members.contains(c))
+            .map(|(k, _)| k)
+            .collect()
+    }
+
+    /// Remove members from the most crowded cells of an adaptive grid
+    /// over the objective space until the capacity is respected
+    fn prune(&mut self) {
+        while self.members.len() > self.capacity {
+            let cells = grid_cells(&self.members, ARCHIVE_GRID_DIVISIONS);
+
+            let mut counts: std::collections::HashMap<&[usize], usize> =
+                std::collections::HashMap::new();
+            for c in cells.iter() {
+                *counts.entry(c.as_slice()).or_default() += 1;
+            }
+            let crowded = counts.into_iter().max_by_key(|(_, n)| *n).unwrap().0;
+
+            let in_cell: Vec<usize> = (0..cells.len())
+                .filter(|i| cells[*i].as_slice() == crowded)
+                .collect();
+            let i = *in_cell.choose(&mut rand::thread_rng()).unwrap();
+            self.members.swap_remove(i);
+        }
+    }
+}
+
+/// Cell of each solution in a grid spanning the bounding box of the solutions
+fn grid_cells<T: CanDominate>(solutions: &[T], divisions: usize) -> Vec<Vec<usize>> {
+    let m = solutions.first().map(|s| s.objectives().len()).unwrap_or(0);
+
+    let mut min = vec![f32::INFINITY; m];
+    let mut max = vec![f32::NEG_INFINITY; m];
+    for s in solutions {
+        for (k, v) in s.objectives().iter().enumerate() {
+            min[k] = min[k].min(*v);
+            max[k] = max[k].max(*v);
+        }
+    }
+
+    solutions
+        .iter()
+        .map(|s| {
+            s.objectives()
+                .iter()
+                .enumerate()
+                .map(|(k, v)| {
+                    if max[k] == min[k] {
+                        0
+                    } else {
+                        let cell = ((v - min[k]) / (max[k] - min[k]) * divisions as f32) as usize;
+                        cell.min(divisions - 1)
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use itertools::Itertools;
+
+    #[derive(Debug, PartialEq)]
+    struct Point {
+        a: i8,
+        b: i8,
+        c: i8,
+    }
+
+    impl Point {
+        fn values(&self) -> [f32; 3] {
+            [self.a as f32, self.b as f32, self.c as f32]
+        }
+    }
+
+    impl CanDominate for Point {
+        fn dominates(&self, other: &Self) -> bool {
+            dominates(&self.values(), &other.values())
+        }
+
+        fn compare_lexicographic(&self, other: &Self) -> Ordering {
+            compare_lexicographic(&self.values(), &other.values())
+        }
+    }
+
+    fn to_strings(data: Vec<Point>) -> Vec<String> {
+        let mut strings = vec![];
+        for d in data {
+            strings.push(format!("{},{},{}", d.a, d.b, d.c));
+        }
+        strings
+    }
+
+    #[test]
+    fn test_dominates() {
+        assert!(dominates(&[1.0, 2.0], &[1.0, 1.0]));
+        assert!(!dominates(&[1.0, 1.0], &[1.0, 1.0]));
+        assert!(!dominates(&[2.0, 0.0], &[1.0, 1.0]));
+
+        // Values within the same unit box of the first objective are not told apart
+        let epsilon = [1.0, 0.0];
+        assert!(!dominates(&[1.1, 2.0], &[1.9, 1.0]));
+        assert!(epsilon_dominates(&[1.1, 2.0], &[1.9, 1.0], &epsilon));
+        assert!(epsilon_dominates(&[1.5, 1.0], &[1.2, 1.0], &epsilon));
+        assert!(!epsilon_dominates(&[1.5, 1.0], &[1.2, 2.0], &epsilon));
+        assert!(epsilon_dominates(&[2.1, 2.0], &[1.9, 2.0], &epsilon));
+        assert!(!epsilon_dominates(&[1.9, 2.0], &[1.5, 3.0], &epsilon));
+
+        assert_eq!(
+            compare_lexicographic(&[1.0, 2.0], &[1.0, 1.0]),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_epsilon(&[1.5, 1.0], &[1.2, 2.0], &epsilon),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_sort_filter() {
+        use rand::{Rng, SeedableRng};
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let data: Vec<Objectives> = (0..300)
+            .map(|_| Objectives((0..4).map(|_| rng.gen_range(0..5) as f32).collect()))
+            .collect();
+
+        let sorted = |mut v: Vec<Objectives>| {
+            v.sort_by(|a, b| compare_lexicographic(&a.0, &b.0));
+            v
+        };
+        let expected = sorted(naive(data.clone()));
+        assert_eq!(sorted(sort_filter(data.clone())), expected);
+        assert_eq!(sorted(kung_recursive(data.clone())), expected);
+        assert_eq!(sorted(best_order_filter(data.clone())), expected);
+
+        // Without temperature, only the front is kept
+        let data: Vec<MOSA> = data
+            .iter()
+            .map(|o| MOSA(o.0.iter().map(|v| *v as i32).collect()))
+            .collect();
+        assert_eq!(sort_filter_mosa(data.clone(), 1e-9).len(), expected.len());
+        assert_eq!(best_order_filter_mosa(data, 1e-9).len(), expected.len());
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Boxed(Vec<f32>);
+
+    impl CanDominate for Boxed {
+        fn dominates(&self, other: &Self) -> bool {
+            epsilon_dominates(&self.0, &other.0, &[1.0, 1.0])
+        }
+
+        fn compare_lexicographic(&self, other: &Self) -> Ordering {
+            compare_epsilon(&self.0, &other.0, &[1.0, 1.0])
+        }
+
+        fn objectives(&self) -> &[f32] {
+            &self.0
+        }
+
+        fn sort_keys(&self) -> Vec<f32> {
+            boxes(&self.0, &[1.0, 1.0])
+        }
+    }
+
+    #[test]
+    fn test_best_order_epsilon() {
+        use rand::{Rng, SeedableRng};
+
+        // A solution may dominate another with a worse value, in a better box
+        let a = Boxed(vec![2.0, 1.1]);
+        let b = Boxed(vec![1.0, 1.9]);
+        assert!(a.dominates(&b));
+        assert_eq!(best_order_filter(vec![b, a.clone()]), vec![a]);
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let data: Vec<Boxed> = (0..300)
+            .map(|_| Boxed(vec![rng.gen_range(0.0..5.0), rng.gen_range(0.0..5.0)]))
+            .collect();
+        let sorted = |mut v: Vec<Boxed>| {
+            v.sort_by(|a, b| compare_lexicographic(&a.0, &b.0));
+            v
+        };
+        assert_eq!(sorted(best_order_filter(data.clone())), sorted(naive(data)));
+    }
+
+    #[test]
+    fn test_kung_ties() {
+        // Equal first objectives must not hide a dominated solution
+        let data = objectives(&[[1.0, 2.0], [1.0, 1.0], [1.0, 3.0], [0.0, 5.0]]);
+        let front = kung_recursive(data);
+
+        assert_eq!(front.len(), 2);
+        assert!(front.contains(&Objectives(vec![1.0, 3.0])));
+        assert!(front.contains(&Objectives(vec![0.0, 5.0])));
+    }
+
+    #[test]
+    fn test_kung_recursive() {
+        let data = vec![
+            Point { a: 1, b: 1, c: 1 },
+            Point { a: 2, b: 2, c: 2 },
+            Point { a: 1, b: 3, c: 1 },
+            Point { a: 1, b: 3, c: 1 },
+        ];
+
+        let data = kung_recursive(data);
+        let strings = to_strings(data);
+
+        assert!(strings.iter().filter(|&s| s == "1,1,1").count() == 0);
+        assert!(strings.iter().filter(|&s| s == "2,2,2").count() == 1);
+        assert!(strings.iter().filter(|&s| s == "1,3,1").count() == 2);
+    }
+
+    #[test]
+    fn test_random_frontline() {
+        let data = vec![
+            Point { a: 1, b: 1, c: 1 },
+            Point { a: 2, b: 2, c: 2 },
+            Point { a: 1, b: 3, c: 1 },
+            Point { a: 1, b: 3, c: 1 },
+        ];
+
+        let data = random_frontline(data, 3);
+        let strings = to_strings(data);
+
+        assert!(strings.len() == 2);
+        assert!(strings.iter().filter(|&s| s == "1,1,1").count() == 0);
+        assert!(strings.iter().filter(|&s| s == "2,2,2").count() == 1);
+        assert!(strings.iter().filter(|&s| s == "1,3,1").count() == 1); // Dedup
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct MOSA(Vec<i32>);
+
+    impl CanDominate for MOSA {
+        fn dominates(&self, other: &Self) -> bool {
+            self.0.iter().zip(&other.0).all(|(a, b)| a >= b) && self.0 != other.0
+        }
+
+        fn compare_lexicographic(&self, other: &Self) -> Ordering {
+            self.0.cmp(&other.0)
+        }
+
+        fn sum(&self) -> f32 {
+            self.0.iter().sum::<i32>() as f32
+        }
+
+        fn sort_keys(&self) -> Vec<f32> {
+            self.0.iter().map(|v| *v as f32).collect()
+        }
+    }
+
+    #[test]
+    fn test_mosa() {
+        let original = vec![
+            MOSA(vec![1, 1, 1]),
+            MOSA(vec![2, 2, 2]),
+            MOSA(vec![1, 3, 1]),
+            MOSA(vec![1, 3, 1]),
+        ];
+
+        let data = random_mosa(original.clone(), 3, 3.0);
+
+        let strings: Vec<String> = data
+            .iter()
+            .map(|v| v.0.iter().map(|i| format!("{i}")).join(","))
+            .collect();
+
+        assert!(strings.iter().filter(|&s| s == "2,2,2").count() == 1);
+        assert!(strings.iter().filter(|&s| s == "1,3,1").count() == 1); // Dedup
+
+        for _ in 0..500 {
+            let data = random_mosa(original.clone(), 3, 3.0);
+
+            if data.iter().filter(|s| s.0 == vec![1, 1, 1]).count() == 1 {
+                return;
+            }
+        }
+
+        panic!("That's pretty uncommon");
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Objectives(Vec<f32>);
+
+    impl CanDominate for Objectives {
+        fn dominates(&self, other: &Self) -> bool {
+            dominates(&self.0, &other.0)
+        }
+
+        fn compare_lexicographic(&self, other: &Self) -> Ordering {
+            compare_lexicographic(&self.0, &other.0)
+        }
+
+        fn objectives(&self) -> &[f32] {
+            &self.0
+        }
+    }
+
+    fn objectives(data: &[[f32; 2]]) -> Vec<Objectives> {
+        data.iter().map(|d| Objectives(d.to_vec())).collect()
+    }
+
+    #[test]
+    fn test_non_dominated_sort() {
+        let data = objectives(&[[3.0, 1.0], [1.0, 3.0], [2.0, 2.0], [0.0, 0.0], [1.0, 1.5]]);
+
+        let fronts = non_dominated_sort(data);
+
+        assert_eq!(fronts.len(), 3);
+        assert_eq!(fronts[0].len(), 3);
+        assert_eq!(fronts[1], objectives(&[[1.0, 1.5]]));
+        assert_eq!(fronts[2], objectives(&[[0.0, 0.0]]));
+    }
+
+    #[test]
+    fn test_crowding_distance() {
+        let front = objectives(&[[0.0, 4.0], [1.0, 3.0], [3.0, 1.0], [4.0, 0.0]]);
+
+        let d = crowding_distance(&front);
+
+        assert!(d[0].is_infinite() && d[3].is_infinite());
+        assert_eq!(d[1], 1.5);
+        assert_eq!(d[2], 1.5);
+    }
+
+    #[test]
+    fn test_nsga2() {
+        let data = objectives(&[[0.0, 4.0], [1.0, 3.0], [1.1, 2.9], [4.0, 0.0], [0.0, 0.0]]);
+
+        let selected = nsga2(data, 3);
+
+        // Boundaries are kept, then the less crowded of the two middle ones
+        assert_eq!(selected.len(), 3);
+        assert!(selected.contains(&Objectives(vec![0.0, 4.0])));
+        assert!(selected.contains(&Objectives(vec![4.0, 0.0])));
+        assert!(!selected.contains(&Objectives(vec![0.0, 0.0])));
+    }
+
+    #[test]
+    fn test_best_by_sum() {
+        let data = vec![
+            MOSA(vec![1, 1, 1]),
+            MOSA(vec![0, 5, 0]),
+            MOSA(vec![2, 2, 2]),
+            MOSA(vec![2, 2, 2]),
+        ];
+
+        assert_eq!(
+            best_by_sum(data, 2),
+            vec![MOSA(vec![2, 2, 2]), MOSA(vec![0, 5, 0])]
+        );
+    }
+
+    #[test]
+    fn test_archive() {
+        let mut archive = Archive::default();
+
+        archive.update(objectives(&[[1.0, 1.0], [0.0, 2.0]]));
+        archive.update(objectives(&[[2.0, 3.0], [0.0, 2.0]]));
+
+        assert_eq!(archive.members(), objectives(&[[2.0, 3.0]]).as_slice());
+    }
+
+    #[test]
+    fn test_bounded_archive() {
+        let mut archive = Archive::with_capacity(3);
+
+        // A front crowded around (0, 10), plus its two far ends
+        let mut front = vec![[0.0, 10.0], [10.0, 0.0]];
+        for i in 1..6 {
+            front.push([0.1 * i as f32, 10.0 - 0.1 * i as f32]);
+        }
+        archive.update(objectives(&front));
+
+        assert_eq!(archive.len(), 3);
+        assert!(archive.members().contains(&Objectives(vec![10.0, 0.0])));
+
+        // Dominated candidates are never cloned in
+        archive.update_from(objectives(&[[-1.0, -1.0]]).iter());
+        assert_eq!(archive.len(), 3);
+        assert!(!archive.members().contains(&Objectives(vec![-1.0, -1.0])));
+    }
+}
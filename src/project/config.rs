@@ -12,6 +12,78 @@ fn default_initial_attempts() -> usize {
     3
 }
 
+fn default_initial_population() -> usize {
+    1
+}
+
+fn default_selection() -> String {
+    "mosa".to_owned()
+}
+
+fn default_optimizer() -> String {
+    "mosa".to_owned()
+}
+
+fn default_optimizer_tabu_size() -> usize {
+    100
+}
+
+fn default_lahc_length() -> usize {
+    50
+}
+
+fn default_deluge_rate() -> f32 {
+    0.001
+}
+
+fn default_islands() -> usize {
+    1
+}
+
+fn default_migration_interval() -> usize {
+    10
+}
+
+fn default_migration_size() -> usize {
+    2
+}
+
+fn default_island_temperature_factor() -> f32 {
+    0.5
+}
+
+fn default_adaptive_policy() -> String {
+    "heuristic".to_owned()
+}
+
+fn default_adaptive_learning_rate() -> f32 {
+    0.3
+}
+
+fn default_adaptive_temperature() -> f32 {
+    2.0
+}
+
+fn default_ucb_exploration() -> f32 {
+    1.0
+}
+
+fn default_matching_min_probability() -> f32 {
+    0.05
+}
+
+fn default_crossover() -> String {
+    "none".to_owned()
+}
+
+fn default_crossover_children() -> usize {
+    10
+}
+
+fn default_archive_size() -> usize {
+    100
+}
+
 #[derive(Default, Debug, Deserialize)]
 pub struct Config {
     pub slots_per_day: usize,
@@ -26,6 +98,12 @@ pub struct Config {
     pub initial_method: String,
     #[serde(default = "default_initial_attempts")]
     pub initial_attempts: usize,
+    /// Race the initial attempts on all cores
+    #[serde(default)]
+    pub initial_parallel: bool,
+    /// Number of distinct initial solutions the optimization starts from
+    #[serde(default = "default_initial_population")]
+    pub initial_population: usize,
 
     pub max_iter_initial: usize,
     pub max_iter: usize,
@@ -33,16 +111,113 @@ pub struct Config {
     pub population_size: usize,
     pub initial_temperature: f32,
 
+    /// Strategy of the optimization phase: "mosa", "tabu", "lahc" or "great_deluge"
+    #[serde(default = "default_optimizer")]
+    pub optimizer: String,
+    /// Number of recent moves the tabu optimizer forbids to undo
+    #[serde(default = "default_optimizer_tabu_size")]
+    pub optimizer_tabu_size: usize,
+    /// Iterations back late acceptance compares to
+    #[serde(default = "default_lahc_length")]
+    pub lahc_length: usize,
+    /// Rise of the great deluge water level per iteration, relative to the first score
+    #[serde(default = "default_deluge_rate")]
+    pub deluge_rate: f32,
+
+    /// Number of populations evolving in parallel
+    #[serde(default = "default_islands")]
+    pub islands: usize,
+    /// Iterations between two migrations of solutions to the next island
+    #[serde(default = "default_migration_interval")]
+    pub migration_interval: usize,
+    /// Number of elite solutions each island sends
+    #[serde(default = "default_migration_size")]
+    pub migration_size: usize,
+    /// Ratio of the MOSA temperature of an island to the one of the previous island
+    #[serde(default = "default_island_temperature_factor")]
+    pub island_temperature_factor: f32,
+
+    /// Recombination of the population: "none", "day" or "kind"
+    #[serde(default = "default_crossover")]
+    pub crossover: String,
+    /// Number of children bred from random pairs of the population per iteration
+    #[serde(default = "default_crossover_children")]
+    pub crossover_children: usize,
+
+    /// How the MOSA optimizer selects the next population: "mosa", "nsga2", or "weighted",
+    /// the largest sums of weighted scores
+    #[serde(default = "default_selection")]
+    pub selection: String,
+
+    /// Normalise scores between the best and worst ones (or the criterion range)
+    /// before summing them, in the MOSA energy and the weighted selection
+    #[serde(default)]
+    pub normalize: bool,
+
     /// If change is smaller than it, penalty will be applied
     pub penalty_threshold: f32,
     /// How many steps to decrease to avg
     pub penalty_factor: f32,
 
     pub expected_graded_num: usize,
+
+    /// How neighborhoods are weighted from their rewards: "heuristic", "softmax", "ucb" or "matching"
+    #[serde(default = "default_adaptive_policy")]
+    pub adaptive_policy: String,
+    /// Weight of the last reward in the averages of the softmax and matching policies
+    #[serde(default = "default_adaptive_learning_rate")]
+    pub adaptive_learning_rate: f32,
+    /// Of the softmax policy, lower is greedier
+    #[serde(default = "default_adaptive_temperature")]
+    pub adaptive_temperature: f32,
+    /// Weight of the confidence bound in the UCB policy
+    #[serde(default = "default_ucb_exploration")]
+    pub ucb_exploration: f32,
+    /// Lowest probability of a neighborhood in the matching policy
+    #[serde(default = "default_matching_min_probability")]
+    pub matching_min_probability: f32,
+
     pub history_size: usize,
+
+    /// Max number of non-dominated solutions kept over the whole run
+    #[serde(default = "default_archive_size")]
+    pub archive_size: usize,
+
+    /// Reference point of the logged hypervolume. Defaults to just below the
+    /// worst scores of the first iteration
+    #[serde(default)]
+    pub hypervolume_reference: Option<Vec<f32>>,
+    /// Log the hypervolume, IGD and spread of the population at every iteration.
+    /// Costly with many objectives
+    #[serde(default)]
+    pub metrics: bool,
+    /// Known front the IGD is measured against, not logged without it
+    #[serde(default)]
+    pub igd_reference: Option<Vec<Vec<f32>>>,
 }
 
 impl Config {
+    /// A week of `days` with `slots_per_day` each. Settings which may be omitted
+    /// in config.json get the same defaults, the required ones are zero.
+    pub fn new(days: usize, slots_per_day: usize) -> Config {
+        let mut c: Config = serde_json::from_value(serde_json::json!({
+            "days": days,
+            "slots_per_day": slots_per_day,
+            "initial_method": "",
+            "max_iter_initial": 0,
+            "max_iter": 0,
+            "population_size": 0,
+            "initial_temperature": 0,
+            "penalty_threshold": 0,
+            "penalty_factor": 0,
+            "expected_graded_num": 0,
+            "history_size": 0,
+        }))
+        .unwrap();
+        c.num_slots = days * slots_per_day;
+        c
+    }
+
     pub fn iter_slots(&self) -> Range<usize> {
         0..self.num_slots
     }
@@ -97,4 +272,13 @@ mod test {
         assert!(c.slots_of_day(1).contains(&19));
         assert!(!c.slots_of_day(1).contains(&20));
     }
+
+    #[test]
+    fn test_new() {
+        let c = Config::new(2, 3);
+        assert_eq!(c.iter_slots(), 0..6);
+        assert_eq!(c.tabu_size, default_tabu_size());
+        assert_eq!(c.islands, default_islands());
+        assert_eq!(c.initial_attempts, default_initial_attempts());
+    }
 }
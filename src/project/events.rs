@@ -4,6 +4,7 @@ use std::{
 };
 
 use rand::seq::SliceRandom;
+use roaring::RoaringBitmap;
 use serde::Deserialize;
 
 use crate::{fatal, must_open, utils};
@@ -42,6 +43,10 @@ pub struct Events {
 
     /// This field is updated lazily
     attendees: Vec<HashSet<Person>>,
+    /// Whether two events share an attendee, a row of `conflict_words` bits per event.
+    /// Filled with the attendees.
+    conflicts: Vec<u64>,
+    conflict_words: usize,
 }
 
 impl Events {
@@ -53,6 +58,11 @@ impl Events {
         }
     }
 
+    /// Like `kind_name_to_id`, but returns None for unknown kinds
+    pub fn find_kind(&self, name: &str) -> Option<EventKind> {
+        self.kind_name_to_id.get(name).copied()
+    }
+
     pub fn events_with_kind(&self, kind: EventKind) -> HashSet<Event> {
         let mut events = HashSet::new();
         for (i, k) in self.kinds.iter().enumerate() {
@@ -118,6 +128,25 @@ impl Events {
                 self.attendees[e.0].insert(Person(i));
             }
         }
+
+        self.fill_conflicts(people);
+    }
+
+    fn fill_conflicts(&mut self, people: &People) {
+        let words = self.len().div_ceil(64);
+        self.conflict_words = words;
+        self.conflicts = vec![0; self.len() * words];
+
+        for e in 0..self.len() {
+            let mut row = RoaringBitmap::new();
+            for p in &self.attendees[e] {
+                row |= people.events_attended_bitmap(*p);
+            }
+
+            for e2 in row {
+                self.conflicts[e * words + e2 as usize / 64] |= 1 << (e2 % 64);
+            }
+        }
     }
 
     pub fn event_attendees(&self, e: Event) -> &HashSet<Person> {
@@ -125,10 +154,7 @@ impl Events {
     }
 
     pub fn have_people_conflict(&self, e1: Event, e2: Event) -> bool {
-        self.event_attendees(e1)
-            .intersection(self.event_attendees(e2))
-            .count()
-            > 0
+        self.conflicts[e1.0 * self.conflict_words + e2.0 / 64] & (1 << (e2.0 % 64)) != 0
     }
 }
 
@@ -177,5 +203,27 @@ pub fn parse_events<P: AsRef<Path>>(path: P, rooms: &Rooms) -> Events {
         kind_name_to_id,
         kind_id_to_name,
         attendees: vec![],
+        conflicts: vec![],
+        conflict_words: 0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_conflicts() {
+        let project = crate::project::Project::parse("./converted/comp01");
+        let events = &project.events;
+
+        for e1 in events.iter_all() {
+            for e2 in events.iter_all() {
+                let shared = events
+                    .event_attendees(e1)
+                    .intersection(events.event_attendees(e2));
+                assert_eq!(events.have_people_conflict(e1, e2), shared.count() > 0);
+            }
+        }
     }
 }
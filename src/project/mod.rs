@@ -4,6 +4,8 @@ mod config;
 mod events;
 mod people;
 mod rooms;
+#[cfg(test)]
+pub mod test_util;
 
 pub use config::Config;
 pub use events::{Event, EventKind, Events};
@@ -16,6 +18,7 @@ pub struct Project {
     pub events: events::Events,
     pub people: people::People,
     pub criteria: Option<crate::criteria::Criteria>,
+    pub neighborhoods: Vec<Box<dyn crate::neighborhoods::Neighborhood>>,
 }
 
 impl Debug for Project {
@@ -32,6 +35,11 @@ impl Debug for Project {
 
 impl Project {
     pub fn parse<P: AsRef<Path>>(path: P) -> Self {
+        Self::parse_with(path, &crate::criteria::Registry::default())
+    }
+
+    /// Parse a project, resolving criterion types with `registry`
+    pub fn parse_with<P: AsRef<Path>>(path: P, registry: &crate::criteria::Registry) -> Self {
         let config = config::parse_config(&path);
         let rooms = rooms::parse_rooms(&path);
         let mut events = events::parse_events(&path, &rooms);
@@ -44,9 +52,10 @@ impl Project {
             events,
             people,
             criteria: None,
+            neighborhoods: crate::neighborhoods::default_neighborhoods(),
         };
 
-        let c = crate::criteria::parse_criteria(path, &p);
+        let c = crate::criteria::parse_criteria(path, &p, registry);
 
         p.criteria = Some(c);
 
@@ -56,4 +65,13 @@ impl Project {
     pub fn criteria(&self) -> &crate::criteria::Criteria {
         self.criteria.as_ref().unwrap()
     }
+
+    /// Add a neighborhood to be explored by the optimizer
+    pub fn register_neighborhood<N: crate::neighborhoods::Neighborhood + 'static>(
+        &mut self,
+        n: N,
+    ) -> &mut Self {
+        self.neighborhoods.push(Box::new(n));
+        self
+    }
 }
@@ -1,5 +1,6 @@
 use std::{collections::HashSet, path::Path};
 
+use roaring::RoaringBitmap;
 use serde::Deserialize;
 
 use crate::{fatal, must_open};
@@ -18,6 +19,8 @@ pub struct Person(pub usize);
 #[derive(Debug)]
 pub struct People {
     events_attended: Vec<HashSet<Event>>,
+    /// Same as `events_attended`, as bitmaps of event ids
+    events_attended_bitmaps: Vec<RoaringBitmap>,
     names: Vec<String>,
 }
 
@@ -27,10 +30,22 @@ impl People {
         &self.events_attended[p.0]
     }
 
+    pub fn events_attended_bitmap(&self, p: Person) -> &RoaringBitmap {
+        &self.events_attended_bitmaps[p.0]
+    }
+
     pub fn len(&self) -> usize {
         self.names.len()
     }
 
+    pub fn person_name(&self, p: Person) -> &str {
+        &self.names[p.0]
+    }
+
+    pub fn find_person(&self, name: &str) -> Option<Person> {
+        self.names.iter().position(|n| n == name).map(Person)
+    }
+
     pub fn iter_all(&self) -> impl Iterator<Item = Person> {
         (0..self.len()).map(|p| Person(p))
     }
@@ -60,8 +75,14 @@ pub fn parse_people<P: AsRef<Path>>(path: P, events: &Events) -> People {
         events_attended.push(attended)
     }
 
+    let events_attended_bitmaps = events_attended
+        .iter()
+        .map(|attended| attended.iter().map(|e| e.0 as u32).collect())
+        .collect();
+
     People {
         events_attended,
+        events_attended_bitmaps,
         names,
     }
 }
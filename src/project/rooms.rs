@@ -57,6 +57,23 @@ impl Rooms {
         *self.kind_name_to_id.get(name).unwrap()
     }
 
+    pub fn kind_name(&self, kind: &RoomKind) -> &str {
+        self.kind_name_to_id
+            .iter()
+            .find(|(_, k)| *k == kind)
+            .map(|(n, _)| n.as_str())
+            .unwrap()
+    }
+
+    /// Like `kind_name_to_id`, but returns None for unknown kinds
+    pub fn find_kind(&self, name: &str) -> Option<RoomKind> {
+        self.kind_name_to_id.get(name).copied()
+    }
+
+    pub fn find_room(&self, name: &str) -> Option<Room> {
+        self.names.iter().position(|n| n == name).map(Room)
+    }
+
     pub fn len(&self) -> usize {
         self.names.len()
     }
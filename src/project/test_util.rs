@@ -0,0 +1,25 @@
+use super::Project;
+
+/// Two events over the rooms of comp01, attended by a single person:
+/// `a` three times a week at most once a day, `b` twice a week
+pub fn tiny_project(criteria: &str) -> Project {
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path();
+    for f in ["config.json", "rooms.json", "rooms_adj.csv"] {
+        std::fs::copy(format!("./converted/comp01/{f}"), dir.join(f)).unwrap();
+    }
+    std::fs::write(
+        dir.join("events.json"),
+        r#"[{"name": "a", "num_per_week": 3, "max_per_day": 1, "room_kind": "normal"},
+            {"name": "b", "num_per_week": 2, "room_kind": "normal"}]"#,
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("people.json"),
+        r#"[{"name": "p", "attend": ["a", "b"]}]"#,
+    )
+    .unwrap();
+    std::fs::write(dir.join("criteria.json"), criteria).unwrap();
+
+    Project::parse(dir)
+}
@@ -0,0 +1,274 @@
+// Report of hard constraint violations of a solution
+
+use std::{collections::HashMap, fmt::Display, path::Path};
+
+use crate::{
+    export, fatal,
+    optimize::TIMEMAP,
+    project::{Event, Project, Room},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// Several events use the same room in a slot
+    RoomClash {
+        slot: usize,
+        room: String,
+        events: Vec<String>,
+    },
+    /// A person attends several events in a slot
+    PeopleClash {
+        slot: usize,
+        person: String,
+        events: Vec<String>,
+    },
+    /// An event is scheduled more than once
+    Duplicate {
+        event: String,
+        times: usize,
+        places: Vec<String>,
+    },
+    /// An event is not scheduled
+    Missing { event: String },
+    /// Too many events of a kind on one day
+    MaxPerDay {
+        day: usize,
+        event: String,
+        count: usize,
+        max: usize,
+    },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::RoomClash { slot, room, events } => {
+                write!(f, "Room clash at {slot} in {room}: {}", events.join(", "))
+            }
+            Violation::PeopleClash {
+                slot,
+                person,
+                events,
+            } => write!(
+                f,
+                "People clash at {slot} for {person}: {}",
+                events.join(", ")
+            ),
+            Violation::Duplicate {
+                event,
+                times,
+                places,
+            } => write!(
+                f,
+                "Duplicate event: {event} scheduled {times} times, at {}",
+                places.join("; ")
+            ),
+            Violation::Missing { event } => write!(f, "Missing event: {event}"),
+            Violation::MaxPerDay {
+                day,
+                event,
+                count,
+                max,
+            } => write!(f, "Excess num of {event} on day {day}: {count} > {max}"),
+        }
+    }
+}
+
+/// Every violated hard constraint of a solution
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Violations(pub Vec<Violation>);
+
+impl Violations {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Violation> {
+        self.0.iter()
+    }
+}
+
+impl Display for Violations {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No violations");
+        }
+
+        writeln!(f, "{} violations:", self.len())?;
+        for v in self.iter() {
+            writeln!(f, "  {v}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Where an event is placed, like `day 2 slot 3, room 101`
+fn place(t: usize, r: &Room, project: &Project) -> String {
+    format!(
+        "day {} slot {}, room {}",
+        project.config.slots_to_day(t),
+        project.config.offset_in_day(t),
+        project.rooms.room_name(r)
+    )
+}
+
+/// Kind and place of an event, like `Math @ day 2 slot 3, room 101`.
+/// Event indices are shuffled on every parse, so they are not shown.
+pub fn event_name(e: &Event, t: usize, r: &Room, project: &Project) -> String {
+    format!("{} @ {}", project.events.kind_name(e), place(t, r, project))
+}
+
+pub fn find_violations(s: &TIMEMAP, project: &Project) -> Violations {
+    let mut violations = vec![];
+
+    // Duplicate and missing events
+    let mut places = vec![vec![]; project.events.len()];
+    for (t, v) in s.iter().enumerate() {
+        for (e, r) in v {
+            places[e.0].push(place(t, r, project));
+        }
+    }
+    for e in project.events.iter_all() {
+        let event = project.events.kind_name(&e).to_owned();
+        match places[e.0].len() {
+            0 => violations.push(Violation::Missing { event }),
+            1 => {}
+            n => violations.push(Violation::Duplicate {
+                event,
+                times: n,
+                places: std::mem::take(&mut places[e.0]),
+            }),
+        }
+    }
+
+    // Clashes
+    for t in project.config.iter_slots() {
+        let mut rooms: HashMap<_, Vec<String>> = HashMap::new();
+        let mut people: HashMap<_, Vec<String>> = HashMap::new();
+
+        for (e, r) in &s[t] {
+            let name = event_name(e, t, r, project);
+            for p in project.events.event_attendees(*e) {
+                people.entry(*p).or_default().push(name.clone());
+            }
+            rooms.entry(*r).or_default().push(name);
+        }
+
+        let mut rooms: Vec<_> = rooms.into_iter().filter(|(_, v)| v.len() > 1).collect();
+        rooms.sort_by_key(|(r, _)| r.0);
+        for (r, events) in rooms {
+            violations.push(Violation::RoomClash {
+                slot: t,
+                room: project.rooms.room_name(&r).to_owned(),
+                events,
+            });
+        }
+
+        let mut people: Vec<_> = people.into_iter().filter(|(_, v)| v.len() > 1).collect();
+        people.sort_by_key(|(p, _)| p.0);
+        for (p, events) in people {
+            violations.push(Violation::PeopleClash {
+                slot: t,
+                person: project.people.person_name(p).to_owned(),
+                events,
+            });
+        }
+    }
+
+    // Max per day
+    for day in project.config.days() {
+        let mut counter: HashMap<_, (usize, Event)> = HashMap::new();
+        for (e, _) in project.config.slots_of_day(day).flat_map(|t| &s[t]) {
+            counter.entry(project.events.kind(e)).or_insert((0, *e)).0 += 1;
+        }
+
+        let mut counter: Vec<_> = counter.into_values().collect();
+        counter.sort_by_key(|(_, e)| e.0);
+        for (count, e) in counter {
+            let max = project.events.max_per_day(&e);
+            if count > max {
+                violations.push(Violation::MaxPerDay {
+                    day,
+                    event: project.events.kind_name(&e).to_owned(),
+                    count,
+                    max,
+                });
+            }
+        }
+    }
+
+    Violations(violations)
+}
+
+/// Check the solutions exported to `path`, printing the violations of each.
+/// Gives the number of invalid solutions.
+pub fn validate<P: AsRef<Path>>(path: P, project: &Project) -> usize {
+    let path = path.as_ref();
+    let solutions = match export::read(path, project) {
+        Ok(s) => s,
+        Err(e) => fatal!("Failed to read {}: {e}", path.display()),
+    };
+
+    let mut invalid = 0;
+    for (i, s) in solutions.into_iter().enumerate() {
+        let violations = find_violations(&s, project);
+        if !violations.is_empty() {
+            invalid += 1;
+        }
+        println!("Solution {i}: {violations}");
+    }
+
+    invalid
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_find_violations() {
+        let project = Project::parse("./converted/comp01");
+        let mut events: crate::optimize::TIMEMAP =
+            project.config.iter_slots().map(|_| vec![]).collect();
+
+        // Two events of the same kind share attendees
+        let kind = project.events.kind(&Event(0));
+        let same_kind: Vec<Event> = project.events.events_with_kind(kind).into_iter().collect();
+        events[0].push((same_kind[0], Room(0)));
+        events[0].push((same_kind[1], Room(0)));
+        events[1].push((same_kind[0], Room(1)));
+
+        let violations = find_violations(&events, &project);
+
+        let count = |f: fn(&Violation) -> bool| violations.iter().filter(|v| f(v)).count();
+        assert_eq!(
+            count(|v| matches!(v, Violation::RoomClash { slot: 0, .. })),
+            1
+        );
+        assert!(count(|v| matches!(v, Violation::PeopleClash { slot: 0, .. })) >= 1);
+        assert_eq!(
+            count(|v| matches!(v, Violation::Duplicate { times: 2, .. })),
+            1
+        );
+        assert_eq!(
+            count(|v| matches!(v, Violation::Missing { .. })),
+            project.events.len() - 2
+        );
+
+        let name = project.events.kind_name(&same_kind[0]);
+        assert!(format!("{violations}").contains(name));
+
+        // Events are named by where they are, as in exported files
+        let room = project.rooms.room_name(&Room(1));
+        let place = format!("{name} @ day 0 slot 1, room {room}");
+        assert_eq!(event_name(&same_kind[0], 1, &Room(1), &project), place);
+        assert!(violations.iter().any(|v| matches!(
+            v,
+            Violation::Duplicate { places, .. } if places.contains(&format!("day 0 slot 1, room {room}"))
+        )));
+    }
+}